@@ -0,0 +1,11 @@
+#![no_main]
+
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate shakmaty;
+
+use shakmaty::iccf::Iccf;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Iccf::from_bytes(data);
+});
@@ -0,0 +1,296 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Command-line tool for perft, FEN validation and notation conversion.
+
+extern crate shakmaty;
+
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::io::prelude::*;
+use std::process;
+
+use shakmaty::{Chess, Position, Perft, CastlingMode};
+use shakmaty::fen::Fen;
+use shakmaty::perft::Suite;
+use shakmaty::san::{self, San};
+use shakmaty::uci::Uci;
+
+const USAGE: &str = "\
+Usage: shakmaty <command> [<args>]
+
+Commands:
+    perft <fen> <depth> [--divide] [--chess960] [--threads <n>] [--hash <bytes>]
+    suite <file> [--node-limit <nodes>] [--threads <n>] [--hash <bytes>]
+    validate <fen> [--strict]
+    legals <fen> [--chess960]
+    san2uci <fen> <san>... [--chess960]
+    uci2san <fen> <uci>... [--chess960]
+
+Use `startpos` instead of a FEN for the standard starting position.
+With --chess960 castling moves are written and read as king to rook (e1h1)
+instead of standard notation (e1g1). With --strict validation also rejects
+impossible checks and material.";
+
+fn usage_error<T>(msg: &str) -> Result<T, Box<Error>> {
+    Err(msg.into())
+}
+
+fn parse_fen(fen: &str) -> Result<Fen, Box<Error>> {
+    if fen == "startpos" {
+        Ok(Fen::default())
+    } else {
+        Ok(fen.parse()?)
+    }
+}
+
+fn parse_position(fen: &str) -> Result<Chess, Box<Error>> {
+    Ok(parse_fen(fen)?.position()?)
+}
+
+/// Splits `--flag <value>` style options from positional arguments.
+struct Args {
+    positional: Vec<String>,
+    flags: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    fn parse(args: Vec<String>, with_value: &[&str]) -> Result<Args, Box<Error>> {
+        let mut positional = Vec::new();
+        let mut flags = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg.starts_with("--") {
+                if with_value.contains(&arg.as_str()) {
+                    match args.next() {
+                        Some(value) => flags.push((arg, Some(value))),
+                        None => return usage_error(&format!("missing value for {}", arg)),
+                    }
+                } else {
+                    flags.push((arg, None));
+                }
+            } else {
+                positional.push(arg);
+            }
+        }
+
+        Ok(Args { positional, flags })
+    }
+
+    /// Gets the positional arguments, if there are exactly `n` of them.
+    fn exactly(&self, n: usize) -> Option<&[String]> {
+        if self.positional.len() == n {
+            Some(&self.positional)
+        } else {
+            None
+        }
+    }
+
+    fn has(&self, flag: &str) -> bool {
+        self.flags.iter().any(|&(ref f, _)| f == flag)
+    }
+
+    fn value(&self, flag: &str) -> Option<&str> {
+        self.flags.iter().rev()
+            .filter(|&&(ref f, _)| f == flag)
+            .filter_map(|&(_, ref v)| v.as_ref().map(|v| v.as_str()))
+            .next()
+    }
+
+    fn perft_opts(&self) -> Result<Perft, Box<Error>> {
+        let mut perft = Perft::new();
+        if let Some(threads) = self.value("--threads") {
+            perft.threads(threads.parse()?);
+        }
+        if let Some(hash) = self.value("--hash") {
+            perft.hash_size(hash.parse()?);
+        }
+        Ok(perft)
+    }
+
+    fn castling_mode(&self) -> CastlingMode {
+        CastlingMode::from_chess960(self.has("--chess960"))
+    }
+}
+
+fn cmd_perft(args: Vec<String>) -> Result<(), Box<Error>> {
+    let args = Args::parse(args, &["--threads", "--hash"])?;
+    let (fen, depth) = match args.exactly(2) {
+        Some(positional) => (&positional[0], &positional[1]),
+        None => return usage_error("expected <fen> <depth>"),
+    };
+
+    let pos = parse_position(fen)?;
+    let depth: u8 = depth.parse()?;
+    let perft = args.perft_opts()?;
+
+    if args.has("--divide") {
+        let mut total = 0;
+        for (m, nodes) in perft.divide(&pos, depth) {
+            println!("{} {}", Uci::from_move(&m, args.castling_mode()), nodes);
+            total += nodes;
+        }
+        println!();
+        println!("{}", total);
+    } else {
+        println!("{}", perft.count(&pos, depth));
+    }
+
+    Ok(())
+}
+
+fn cmd_suite(args: Vec<String>) -> Result<(), Box<Error>> {
+    let args = Args::parse(args, &["--node-limit", "--threads", "--hash"])?;
+    let path = match args.exactly(1) {
+        Some(positional) => &positional[0],
+        None => return usage_error("expected <file>"),
+    };
+
+    let node_limit = match args.value("--node-limit") {
+        Some(limit) => limit.parse()?,
+        None => ::std::u64::MAX,
+    };
+    let perft = args.perft_opts()?;
+
+    let mut buffer = Vec::new();
+    File::open(path)?.read_to_end(&mut buffer)?;
+    let suite = Suite::from_bytes(&buffer)?;
+
+    if suite.variant != "chess" {
+        return usage_error(&format!("unsupported variant: {}", suite.variant));
+    }
+
+    let failures = suite.run::<Chess>(&perft, node_limit);
+    for failure in &failures {
+        println!("{}", failure);
+    }
+
+    if !failures.is_empty() {
+        println!();
+        println!("{} failures", failures.len());
+        process::exit(1);
+    }
+
+    println!("ok");
+    Ok(())
+}
+
+fn cmd_validate(args: Vec<String>) -> Result<(), Box<Error>> {
+    let args = Args::parse(args, &[])?;
+    let fen = match args.exactly(1) {
+        Some(positional) => parse_fen(&positional[0])?,
+        None => return usage_error("expected <fen>"),
+    };
+
+    let result = if args.has("--strict") {
+        Chess::from_setup_strict(&fen)
+    } else {
+        Chess::from_setup(&fen)
+    };
+
+    match result {
+        Ok(_) => {
+            println!("ok");
+            Ok(())
+        },
+        Err(errors) => {
+            // The Debug representation lists the names of all set flags.
+            for name in format!("{:?}", errors).split(" | ") {
+                println!("{}", name);
+            }
+            process::exit(1);
+        },
+    }
+}
+
+fn cmd_legals(args: Vec<String>) -> Result<(), Box<Error>> {
+    let args = Args::parse(args, &[])?;
+    let pos = match args.exactly(1) {
+        Some(positional) => parse_position(&positional[0])?,
+        None => return usage_error("expected <fen>"),
+    };
+
+    for m in pos.legals() {
+        println!("{} {}", Uci::from_move(&m, args.castling_mode()), san::san_plus(pos.clone(), &m));
+    }
+
+    Ok(())
+}
+
+fn cmd_san2uci(args: Vec<String>) -> Result<(), Box<Error>> {
+    let args = Args::parse(args, &[])?;
+    let (fen, moves) = match args.positional.split_first() {
+        Some((fen, moves)) => (fen, moves),
+        None => return usage_error("expected <fen> <san>..."),
+    };
+
+    let mut pos = parse_position(fen)?;
+
+    for san in moves {
+        let m = san.parse::<San>()?.to_move(&pos)?;
+        println!("{}", Uci::from_move(&m, args.castling_mode()));
+        pos.play_unchecked(&m);
+    }
+
+    Ok(())
+}
+
+fn cmd_uci2san(args: Vec<String>) -> Result<(), Box<Error>> {
+    let args = Args::parse(args, &[])?;
+    let (fen, moves) = match args.positional.split_first() {
+        Some((fen, moves)) => (fen, moves),
+        None => return usage_error("expected <fen> <uci>..."),
+    };
+
+    let mut pos = parse_position(fen)?;
+
+    for uci in moves {
+        let m = uci.parse::<Uci>()?.to_move_with_mode(&pos, args.castling_mode())?;
+        println!("{}", san::san_plus(pos.clone(), &m));
+        pos.play_unchecked(&m);
+    }
+
+    Ok(())
+}
+
+fn try_main() -> Result<(), Box<Error>> {
+    let mut args = env::args().skip(1);
+    let command = args.next();
+    let args: Vec<String> = args.collect();
+
+    match command.as_ref().map(|c| c.as_str()) {
+        Some("perft") => cmd_perft(args),
+        Some("suite") => cmd_suite(args),
+        Some("validate") => cmd_validate(args),
+        Some("legals") => cmd_legals(args),
+        Some("san2uci") => cmd_san2uci(args),
+        Some("uci2san") => cmd_uci2san(args),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        },
+        _ => usage_error(USAGE),
+    }
+}
+
+fn main() {
+    if let Err(err) = try_main() {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
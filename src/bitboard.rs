@@ -192,6 +192,63 @@ impl Bitboard {
         }
     }
 
+    /// Flip the bitboard vertically, i.e. mirror the ranks.
+    #[inline]
+    pub fn flip_vertical(self) -> Bitboard {
+        Bitboard(self.0.swap_bytes())
+    }
+
+    /// Flip the bitboard horizontally, i.e. mirror the files.
+    #[inline]
+    pub fn flip_horizontal(self) -> Bitboard {
+        let k1 = 0x5555_5555_5555_5555;
+        let k2 = 0x3333_3333_3333_3333;
+        let k4 = 0x0f0f_0f0f_0f0f_0f0f;
+        let mut x = self.0;
+        x = ((x >> 1) & k1) | ((x & k1) << 1);
+        x = ((x >> 2) & k2) | ((x & k2) << 2);
+        x = ((x >> 4) & k4) | ((x & k4) << 4);
+        Bitboard(x)
+    }
+
+    /// Flip the bitboard at the a1-h8 diagonal.
+    #[inline]
+    pub fn flip_diagonal(self) -> Bitboard {
+        let k1 = 0x5500_5500_5500_5500;
+        let k2 = 0x3333_0000_3333_0000;
+        let k4 = 0x0f0f_0f0f_0000_0000;
+        let mut x = self.0;
+        let t = k4 & (x ^ (x << 28));
+        x ^= t ^ (t >> 28);
+        let t = k2 & (x ^ (x << 14));
+        x ^= t ^ (t >> 14);
+        let t = k1 & (x ^ (x << 7));
+        x ^= t ^ (t >> 7);
+        Bitboard(x)
+    }
+
+    /// Flip the bitboard at the h1-a8 diagonal.
+    #[inline]
+    pub fn flip_anti_diagonal(self) -> Bitboard {
+        let k1 = 0xaa00_aa00_aa00_aa00;
+        let k2 = 0xcccc_0000_cccc_0000;
+        let k4 = 0xf0f0_f0f0_0f0f_0f0f;
+        let mut x = self.0;
+        let t = x ^ (x << 36);
+        x ^= k4 & (t ^ (x >> 36));
+        let t = k2 & (x ^ (x << 18));
+        x ^= t ^ (t >> 18);
+        let t = k1 & (x ^ (x << 9));
+        x ^= t ^ (t >> 9);
+        Bitboard(x)
+    }
+
+    /// Rotate the bitboard by 180 degrees.
+    #[inline]
+    pub fn rotate_180(self) -> Bitboard {
+        Bitboard(self.0.reverse_bits())
+    }
+
     /// An iterator over the subsets of this bitboard.
     #[inline]
     pub fn carry_rippler(self) -> CarryRippler {
@@ -501,6 +558,25 @@ mod tests {
         assert!(!Bitboard(1).is_empty());
     }
 
+    #[test]
+    fn test_transformations() {
+        let bb = Bitboard(0).with(Square::A1).with(Square::B3).with(Square::H2);
+
+        fn check(bb: Bitboard, flip_bb: fn(Bitboard) -> Bitboard, flip_sq: fn(Square) -> Square) {
+            assert_eq!(flip_bb(bb), bb.into_iter().map(flip_sq).collect());
+            assert_eq!(flip_bb(flip_bb(bb)), bb);
+        }
+
+        check(bb, Bitboard::flip_vertical, Square::flip_vertical);
+        check(bb, Bitboard::flip_horizontal, Square::flip_horizontal);
+        check(bb, Bitboard::flip_diagonal, Square::flip_diagonal);
+        check(bb, Bitboard::flip_anti_diagonal, Square::flip_anti_diagonal);
+        check(bb, Bitboard::rotate_180, Square::rotate_180);
+
+        assert_eq!(Bitboard::DARK_SQUARES.flip_diagonal(), Bitboard::DARK_SQUARES);
+        assert_eq!(Bitboard::DARK_SQUARES.flip_vertical(), Bitboard::LIGHT_SQUARES);
+    }
+
     #[test]
     fn test_rank() {
         assert_eq!(Bitboard::rank(3), Bitboard(0xff000000));
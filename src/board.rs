@@ -22,6 +22,7 @@ use attacks;
 use square::Square;
 use types::{Color, Role, Piece};
 use bitboard::Bitboard;
+use material::{Material, MaterialChanges};
 
 /// [`Piece`] positions on a board.
 ///
@@ -230,6 +231,74 @@ impl Board {
             (attacks::pawn_attacks(!attacker, sq) & self.pawns()))
     }
 
+    fn transform<F: Fn(Bitboard) -> Bitboard>(&mut self, f: F) {
+        self.occupied = f(self.occupied);
+        for bb in self.occupied_co.iter_mut().chain(self.pieces.iter_mut()) {
+            *bb = f(*bb);
+        }
+        self.promoted = f(self.promoted);
+    }
+
+    /// Flip the board vertically, i.e. mirror the ranks.
+    pub fn flip_vertical(&mut self) {
+        self.transform(Bitboard::flip_vertical);
+    }
+
+    /// Flip the board horizontally, i.e. mirror the files.
+    pub fn flip_horizontal(&mut self) {
+        self.transform(Bitboard::flip_horizontal);
+    }
+
+    /// Flip the board at the a1-h8 diagonal.
+    pub fn flip_diagonal(&mut self) {
+        self.transform(Bitboard::flip_diagonal);
+    }
+
+    /// Flip the board at the h1-a8 diagonal.
+    pub fn flip_anti_diagonal(&mut self) {
+        self.transform(Bitboard::flip_anti_diagonal);
+    }
+
+    /// Rotate the board by 180 degrees.
+    pub fn rotate_180(&mut self) {
+        self.transform(Bitboard::rotate_180);
+    }
+
+    /// Swap the colors of all pieces.
+    pub fn swap_colors(&mut self) {
+        self.occupied_co.swap(0, 1);
+    }
+
+    /// Counts the material on the board.
+    pub fn material(&self) -> Material {
+        Material::from_board(self)
+    }
+
+    /// An iterator over the material changes when playing `moves` from
+    /// this board, with `turn` to move first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Board, Color, Move, Role, Square};
+    ///
+    /// let board = Board::new();
+    /// let moves = [
+    ///     Move::Normal { role: Role::Pawn, from: Square::E2, capture: None, to: Square::E4, promotion: None },
+    ///     Move::Normal { role: Role::Pawn, from: Square::D7, capture: None, to: Square::D5, promotion: None },
+    ///     Move::Normal { role: Role::Pawn, from: Square::E4, capture: Some(Role::Pawn), to: Square::D5, promotion: None },
+    /// ];
+    ///
+    /// let mut changes = board.material_changes(Color::White, &moves);
+    /// let (index, material) = changes.next().unwrap();
+    /// assert_eq!(index, 2);
+    /// assert_eq!(material.to_string(), "KQRRBBNNPPPPPPPPvKQRRBBNNPPPPPPP");
+    /// assert!(changes.next().is_none());
+    /// ```
+    pub fn material_changes<I: IntoIterator>(&self, turn: Color, moves: I) -> MaterialChanges<I::IntoIter> {
+        MaterialChanges::new(self, turn, moves.into_iter())
+    }
+
     pub fn pieces(&self) -> Pieces {
         Pieces {
             pawns: self.pawns(),
@@ -381,4 +450,27 @@ mod tests {
         assert_eq!(board.piece_at(Square::C1), Some(Black.queen()));
         assert!(board.promoted().contains(Square::C1));
     }
+
+    #[test]
+    fn test_transformations() {
+        let mut board: Board = "4k3/8/8/8/8/8/1P6/2~q1K3".parse().expect("valid fen");
+
+        board.rotate_180();
+        assert_eq!(board.piece_at(Square::G7), Some(White.pawn()));
+        assert_eq!(board.piece_at(Square::F8), Some(Black.queen()));
+        assert!(board.promoted().contains(Square::F8));
+
+        board.flip_horizontal();
+        board.swap_colors();
+        assert_eq!(board.piece_at(Square::B7), Some(Black.pawn()));
+        assert_eq!(board.piece_at(Square::E8), Some(Black.king()));
+        assert_eq!(board.piece_at(Square::E1), Some(White.king()));
+
+        board.flip_diagonal();
+        board.flip_anti_diagonal();
+        board.flip_vertical();
+        assert_eq!(board.piece_at(Square::F8), Some(White.queen()));
+        assert_eq!(board.piece_at(Square::G7), Some(Black.pawn()));
+        assert!(board.promoted().contains(Square::F8));
+    }
 }
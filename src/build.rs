@@ -91,6 +91,11 @@ fn main() {
     let mut f = File::create(&dest_path).expect("created attacks.rs");
     generate_basics(&mut f).unwrap();
     generate_sliding_attacks(&mut f).unwrap();
+
+    // generate zobrist.rs
+    let dest_path = Path::new(&out_dir).join("zobrist.rs");
+    let mut f = File::create(&dest_path).expect("created zobrist.rs");
+    generate_zobrist(&mut f).unwrap();
 }
 
 fn generate_basics<W: Write>(f: &mut W) -> io::Result<()> {
@@ -158,3 +163,35 @@ fn generate_sliding_attacks<W: Write>(f: &mut W) -> io::Result<()> {
 
     Ok(())
 }
+
+/// Deterministic xorshift64* generator for the Zobrist keys.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+fn generate_zobrist<W: Write>(f: &mut W) -> io::Result<()> {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+
+    let piece_keys: Vec<u64> = (0..(2 * 6 * 64)).map(|_| rng.next()).collect();
+    let castling_keys: Vec<u64> = (0..64).map(|_| rng.next()).collect();
+    let ep_keys: Vec<u64> = (0..8).map(|_| rng.next()).collect();
+    let pocket_keys: Vec<u64> = (0..(2 * 6 * 16)).map(|_| rng.next()).collect();
+    let check_keys: Vec<u64> = (0..(2 * 4)).map(|_| rng.next()).collect();
+    let turn_keys: Vec<u64> = (0..1).map(|_| rng.next()).collect();
+
+    dump_slice(f, "PIECE_KEYS", "u64", &piece_keys)?;
+    dump_slice(f, "CASTLING_KEYS", "u64", &castling_keys)?;
+    dump_slice(f, "EP_KEYS", "u64", &ep_keys)?;
+    dump_slice(f, "POCKET_KEYS", "u64", &pocket_keys)?;
+    dump_slice(f, "CHECK_KEYS", "u64", &check_keys)?;
+    dump_slice(f, "TURN_KEYS", "u64", &turn_keys)?;
+
+    Ok(())
+}
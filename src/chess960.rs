@@ -0,0 +1,277 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use square::Square;
+use bitboard::Bitboard;
+use types::{White, Black, Role};
+use board::Board;
+use setup::Setup;
+use position::{Position, Chess};
+use fen::Fen;
+
+/// Knight placements on the five squares left after placing bishops and
+/// queen, indexed by the corresponding part of the Scharnagl number.
+const KNIGHTS: [(usize, usize); 10] = [
+    (0, 1), (0, 2), (0, 3), (0, 4), (1, 2),
+    (1, 3), (1, 4), (2, 3), (2, 4), (3, 4),
+];
+
+/// A Chess960 starting position, identified by its Scharnagl number in the
+/// range `0..960`.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Chess960, Role};
+/// use shakmaty::fen::{self, FenOpts};
+///
+/// let start = Chess960::from_number(518).expect("valid number");
+/// assert_eq!(start.back_rank(), [
+///     Role::Rook, Role::Knight, Role::Bishop, Role::Queen,
+///     Role::King, Role::Bishop, Role::Knight, Role::Rook,
+/// ]);
+///
+/// let pos = Chess960::from_number(0).expect("valid number").position();
+/// assert_eq!(fen::fen(&pos, FenOpts::new().shredder(true)),
+///            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Chess960 {
+    number: u16,
+}
+
+impl Chess960 {
+    /// Gets the starting position with the given Scharnagl number, or
+    /// `None` if `number` is not in the range `0..960`.
+    pub fn from_number(number: u16) -> Option<Chess960> {
+        if number < 960 {
+            Some(Chess960 { number })
+        } else {
+            None
+        }
+    }
+
+    /// Gets the Scharnagl number in the range `0..960`.
+    pub fn to_number(self) -> u16 {
+        self.number
+    }
+
+    /// Selects a starting position based on a seed, for example provided by
+    /// a random number generator of the caller. The same seed will always
+    /// select the same position.
+    pub fn from_seed(seed: u64) -> Chess960 {
+        // SplitMix64 finalizer to spread similar seeds.
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+        Chess960 { number: (z % 960) as u16 }
+    }
+
+    /// Tries to identify the Chess960 starting position with the given
+    /// arrangement of pieces on the first rank (from the a-file to the
+    /// h-file).
+    pub fn from_back_rank(back_rank: &[Role; 8]) -> Option<Chess960> {
+        let files = |role: Role| (0..8).filter(move |&file| back_rank[file] == role);
+
+        let light_bishop = files(Role::Bishop).find(|file| file % 2 == 1)?;
+        let dark_bishop = files(Role::Bishop).find(|file| file % 2 == 0)?;
+
+        let rest: Vec<usize> = (0..8).filter(|&file| file != light_bishop && file != dark_bishop).collect();
+        let queen = rest.iter().position(|&file| back_rank[file] == Role::Queen)?;
+
+        let rest: Vec<usize> = rest.into_iter().filter(|&file| back_rank[file] != Role::Queen).collect();
+        let knights = (
+            rest.iter().position(|&file| back_rank[file] == Role::Knight)?,
+            rest.iter().rposition(|&file| back_rank[file] == Role::Knight)?,
+        );
+        let knights = KNIGHTS.iter().position(|&k| k == knights)?;
+
+        let number = (((knights * 6 + queen) * 4 + dark_bishop / 2) * 4 + light_bishop / 2) as u16;
+
+        Chess960::from_number(number).filter(|c| c.back_rank() == *back_rank)
+    }
+
+    /// Tries to identify the Chess960 starting position of a setup. This
+    /// requires the pieces and pawns on their starting squares, White to
+    /// move and full castling rights.
+    pub fn from_setup(setup: &Setup) -> Option<Chess960> {
+        let mut back_rank = [Role::Pawn; 8];
+        for (file, role) in back_rank.iter_mut().enumerate() {
+            let sq = Square::from_coords(file as i8, 0).expect("valid coords");
+            *role = setup.board().role_at(sq)?;
+        }
+
+        Chess960::from_back_rank(&back_rank).filter(|c| {
+            let fen = c.fen();
+            setup.board() == fen.board() &&
+            setup.turn() == fen.turn() &&
+            setup.castling_rights() == fen.castling_rights() &&
+            setup.ep_square().is_none() &&
+            setup.pockets().is_none() &&
+            setup.remaining_checks().is_none()
+        })
+    }
+
+    /// The arrangement of pieces on the first rank, from the a-file to the
+    /// h-file.
+    pub fn back_rank(self) -> [Role; 8] {
+        let mut back_rank = [None; 8];
+        let mut n = self.number as usize;
+
+        back_rank[(n % 4) * 2 + 1] = Some(Role::Bishop);
+        n /= 4;
+        back_rank[(n % 4) * 2] = Some(Role::Bishop);
+        n /= 4;
+
+        let empty: Vec<usize> = (0..8).filter(|&file| back_rank[file].is_none()).collect();
+        back_rank[empty[n % 6]] = Some(Role::Queen);
+        n /= 6;
+
+        let empty: Vec<usize> = (0..8).filter(|&file| back_rank[file].is_none()).collect();
+        let (a, b) = KNIGHTS[n];
+        back_rank[empty[a]] = Some(Role::Knight);
+        back_rank[empty[b]] = Some(Role::Knight);
+
+        let empty: Vec<usize> = (0..8).filter(|&file| back_rank[file].is_none()).collect();
+        back_rank[empty[0]] = Some(Role::Rook);
+        back_rank[empty[1]] = Some(Role::King);
+        back_rank[empty[2]] = Some(Role::Rook);
+
+        let mut result = [Role::Pawn; 8];
+        for (role, placed) in result.iter_mut().zip(back_rank.iter()) {
+            *role = placed.expect("all squares filled");
+        }
+        result
+    }
+
+    /// The board of the starting position.
+    pub fn board(self) -> Board {
+        let back_rank = self.back_rank();
+        let mut board = Board::empty();
+
+        for &color in &[White, Black] {
+            for (file, &role) in back_rank.iter().enumerate() {
+                let sq = Square::from_coords(file as i8, color.fold(0, 7)).expect("valid coords");
+                board.set_piece_at(sq, role.of(color), false);
+
+                let pawn = Square::from_coords(file as i8, color.fold(1, 6)).expect("valid coords");
+                board.set_piece_at(pawn, color.pawn(), false);
+            }
+        }
+
+        board
+    }
+
+    /// The starting position as a [`Fen`] with full castling rights.
+    ///
+    /// [`Fen`]: fen/struct.Fen.html
+    pub fn fen(self) -> Fen {
+        let board = self.board();
+        let castling_rights = board.rooks() & Bitboard::BACKRANKS;
+        Fen {
+            board,
+            castling_rights,
+            ..Fen::default()
+        }
+    }
+
+    /// The starting position.
+    pub fn position(self) -> Chess {
+        Chess::from_setup(&self.fen()).expect("chess960 starting position is legal")
+    }
+
+    /// A Double Fischer Random Chess starting position, with independently
+    /// chosen back ranks for White and Black. See
+    /// [`Chess::from_back_ranks()`].
+    ///
+    /// [`Chess::from_back_ranks()`]: struct.Chess.html#method.from_back_ranks
+    pub fn double(white: Chess960, black: Chess960) -> Chess {
+        Chess::from_back_ranks(&white.back_rank(), &black.back_rank())
+            .expect("double fischer random starting position is legal")
+    }
+}
+
+impl Default for Chess960 {
+    /// The standard chess starting position (number 518).
+    fn default() -> Chess960 {
+        Chess960 { number: 518 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fen::{self, FenOpts};
+
+    fn back_rank_str(c: Chess960) -> String {
+        c.back_rank().iter().map(|role| role.char()).collect()
+    }
+
+    #[test]
+    fn test_numbering() {
+        assert_eq!(back_rank_str(Chess960::from_number(0).unwrap()), "bbqnnrkr");
+        assert_eq!(back_rank_str(Chess960::from_number(1).unwrap()), "bqnbnrkr");
+        assert_eq!(back_rank_str(Chess960::from_number(518).unwrap()), "rnbqkbnr");
+        assert_eq!(back_rank_str(Chess960::from_number(959).unwrap()), "rkrnnqbb");
+        assert_eq!(Chess960::from_number(960), None);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut seen = Vec::new();
+
+        for number in 0..960 {
+            let c = Chess960::from_number(number).unwrap();
+            assert_eq!(Chess960::from_back_rank(&c.back_rank()), Some(c));
+            assert_eq!(Chess960::from_setup(&c.position()), Some(c));
+
+            let back_rank = back_rank_str(c);
+            assert!(!seen.contains(&back_rank));
+            seen.push(back_rank);
+        }
+    }
+
+    #[test]
+    fn test_standard() {
+        let pos = Chess960::default().position();
+        assert_eq!(fen::fen(&pos, &FenOpts::default()), Fen::default().to_string());
+        assert_eq!(Chess960::from_setup(&Chess::default()), Some(Chess960::default()));
+    }
+
+    #[test]
+    fn test_double() {
+        let white = Chess960::from_number(0).unwrap();
+        let black = Chess960::from_number(959).unwrap();
+        let pos = Chess960::double(white, black);
+
+        assert_eq!(fen::fen(&pos, FenOpts::new().shredder(true)),
+                   "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFca - 0 1");
+        assert_eq!(Chess960::from_setup(&pos), None);
+
+        for number in 0..960 {
+            let c = Chess960::from_number(number).unwrap();
+            assert_eq!(fen::fen(&Chess960::double(c, c), &FenOpts::default()),
+                       fen::fen(&c.position(), &FenOpts::default()));
+        }
+    }
+
+    #[test]
+    fn test_from_seed() {
+        assert_eq!(Chess960::from_seed(42), Chess960::from_seed(42));
+        assert!(Chess960::from_seed(42).to_number() < 960);
+    }
+}
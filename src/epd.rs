@@ -0,0 +1,436 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parse and write Extended Position Description with operations.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use shakmaty::{Chess, Square};
+//! use shakmaty::epd::Epd;
+//!
+//! let epd: Epd = "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";".parse()?;
+//! assert_eq!(epd.id(), Some("WAC.001"));
+//!
+//! let pos: Chess = epd.position()?;
+//! let best_moves = epd.bm(&pos)?;
+//! assert_eq!(best_moves[0].to(), Square::G6);
+//!
+//! assert_eq!(epd.to_string(), "2rr3k/pp3pp1/1nnqbN1p/3pN3/2pP4/2P3Q1/PPB4P/R4RK1 w - - bm Qg6; id \"WAC.001\";");
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::error::Error;
+
+use types::Move;
+use position::{Position, PositionError};
+use fen;
+use fen::{Fen, FenOpts, FenError};
+use san::{San, SanError};
+
+/// Errors that can occur when parsing an EPD.
+#[derive(Eq, PartialEq, Debug)]
+pub enum EpdError {
+    InvalidFen(FenError),
+    /// The position has less than four fields.
+    MissingFields,
+    InvalidOperation,
+}
+
+impl EpdError {
+    fn desc(&self) -> &str {
+        match *self {
+            EpdError::InvalidFen(_) => "invalid fen in epd",
+            EpdError::MissingFields => "missing position fields in epd",
+            EpdError::InvalidOperation => "invalid operation in epd",
+        }
+    }
+}
+
+impl fmt::Display for EpdError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EpdError::InvalidFen(ref err) => err.fmt(f),
+            EpdError::MissingFields | EpdError::InvalidOperation => self.desc().fmt(f),
+        }
+    }
+}
+
+impl Error for EpdError {
+    fn description(&self) -> &str {
+        self.desc()
+    }
+}
+
+impl From<FenError> for EpdError {
+    fn from(err: FenError) -> EpdError {
+        EpdError::InvalidFen(err)
+    }
+}
+
+/// An EPD operation like `bm Nf3 Nc3;`.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Operation {
+    pub opcode: String,
+    pub operands: Vec<String>,
+}
+
+/// A parsed EPD: a position and a list of operations.
+///
+/// The `hmvc` and `fmvn` operations also set the halfmove clock and
+/// fullmove number of the position.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Epd {
+    pub fen: Fen,
+    pub operations: Vec<Operation>,
+}
+
+fn tokenize(ops: &[u8]) -> Result<Vec<Operation>, EpdError> {
+    let mut operations = Vec::new();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut token = Vec::new();
+    let mut bytes = ops.iter();
+
+    while let Some(&ch) = bytes.next() {
+        match ch {
+            b'"' => {
+                loop {
+                    match bytes.next() {
+                        Some(&b'"') => break,
+                        Some(&b'\\') => token.push(*bytes.next().ok_or(EpdError::InvalidOperation)?),
+                        Some(&ch) => token.push(ch),
+                        None => return Err(EpdError::InvalidOperation),
+                    }
+                }
+                tokens.push(String::from_utf8(token.split_off(0)).map_err(|_| EpdError::InvalidOperation)?);
+            },
+            b' ' | b'\t' | b';' => {
+                if !token.is_empty() {
+                    tokens.push(String::from_utf8(token.split_off(0)).map_err(|_| EpdError::InvalidOperation)?);
+                }
+                if ch == b';' {
+                    let mut operands = tokens.split_off(0).into_iter();
+                    let opcode = operands.next().ok_or(EpdError::InvalidOperation)?;
+                    operations.push(Operation { opcode, operands: operands.collect() });
+                }
+            },
+            _ => token.push(ch),
+        }
+    }
+
+    if token.is_empty() && tokens.is_empty() {
+        Ok(operations)
+    } else {
+        Err(EpdError::InvalidOperation)
+    }
+}
+
+fn trim_start(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|ch| !ch.is_ascii_whitespace()).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn trim_end(bytes: &[u8]) -> &[u8] {
+    let end = bytes.iter().rposition(|ch| !ch.is_ascii_whitespace()).map_or(0, |n| n + 1);
+    &bytes[..end]
+}
+
+fn is_valid_opcode(opcode: &str) -> bool {
+    !opcode.is_empty() && opcode.len() <= 15 &&
+    opcode.as_bytes()[0].is_ascii_alphabetic() &&
+    opcode.bytes().all(|ch| ch.is_ascii_alphanumeric() || ch == b'_')
+}
+
+fn is_comment(opcode: &str) -> bool {
+    let opcode = opcode.as_bytes();
+    opcode.len() == 2 && opcode[0] == b'c' && opcode[1].is_ascii_digit()
+}
+
+impl Epd {
+    /// An EPD of the given position, without operations.
+    pub fn new(fen: Fen) -> Epd {
+        Epd { fen, operations: Vec::new() }
+    }
+
+    /// Parses an EPD.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EpdError`] if the position or the operations are not
+    /// syntactically valid.
+    ///
+    /// [`EpdError`]: enum.EpdError.html
+    pub fn from_bytes(epd: &[u8]) -> Result<Epd, EpdError> {
+        // Split off the four position fields, separated by runs of
+        // whitespace.
+        let mut position = Vec::new();
+        let mut rest = trim_end(trim_start(epd));
+        for i in 0..4 {
+            if rest.is_empty() {
+                return Err(EpdError::MissingFields);
+            }
+            let end = rest.iter().position(|ch| ch.is_ascii_whitespace()).unwrap_or(rest.len());
+            if i > 0 {
+                position.push(b' ');
+            }
+            position.extend_from_slice(&rest[..end]);
+            rest = trim_start(&rest[end..]);
+        }
+
+        let mut result = Epd::new(Fen::from_bytes(&position)?);
+        result.operations = tokenize(rest)?;
+
+        if !result.operations.iter().all(|op| is_valid_opcode(&op.opcode)) {
+            return Err(EpdError::InvalidOperation);
+        }
+
+        if let Some(hmvc) = result.number("hmvc") {
+            result.fen.halfmove_clock = hmvc;
+        }
+        if let Some(fmvn) = result.number("fmvn") {
+            result.fen.fullmoves = fmvn;
+        }
+
+        Ok(result)
+    }
+
+    /// Set up a [`Position`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PositionError`] if the setup is not a legal position.
+    ///
+    /// [`Position`]: ../trait.Position.html
+    /// [`PositionError`]: ../enum.PositionError.html
+    pub fn position<P: Position>(&self) -> Result<P, PositionError> {
+        self.fen.position()
+    }
+
+    /// Gets the operands of an operation.
+    pub fn operands(&self, opcode: &str) -> Option<&[String]> {
+        self.operations.iter()
+            .find(|op| op.opcode == opcode)
+            .map(|op| &op.operands[..])
+    }
+
+    /// Sets an operation, replacing an existing one with the same opcode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `opcode` is not a valid opcode.
+    pub fn set(&mut self, opcode: &str, operands: Vec<String>) {
+        assert!(is_valid_opcode(opcode));
+        match self.operations.iter_mut().find(|op| op.opcode == opcode) {
+            Some(op) => op.operands = operands,
+            None => self.operations.push(Operation { opcode: opcode.to_owned(), operands }),
+        }
+    }
+
+    /// Removes an operation.
+    pub fn remove(&mut self, opcode: &str) {
+        self.operations.retain(|op| op.opcode != opcode);
+    }
+
+    fn string(&self, opcode: &str) -> Option<&str> {
+        self.operands(opcode).and_then(|ops| ops.first()).map(|s| &s[..])
+    }
+
+    fn number<T: FromStr>(&self, opcode: &str) -> Option<T> {
+        self.string(opcode).and_then(|s| s.parse().ok())
+    }
+
+    /// The position identifier (`id`).
+    pub fn id(&self) -> Option<&str> {
+        self.string("id")
+    }
+
+    /// The comment `c0` to `c9`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not in the range `0..=9`.
+    pub fn comment(&self, n: u8) -> Option<&str> {
+        assert!(n <= 9);
+        self.string(&format!("c{}", n))
+    }
+
+    /// The centipawn evaluation (`ce`).
+    pub fn ce(&self) -> Option<i32> {
+        self.number("ce")
+    }
+
+    /// The analysis count depth (`acd`).
+    pub fn acd(&self) -> Option<u32> {
+        self.number("acd")
+    }
+
+    /// The number of moves to direct mate (`dm`).
+    pub fn dm(&self) -> Option<u32> {
+        self.number("dm")
+    }
+
+    /// Resolves SAN operands to moves in the given position.
+    ///
+    /// Returns an empty list if there is no such operation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanError`] if an operand is not a legal SAN.
+    ///
+    /// [`SanError`]: ../san/enum.SanError.html
+    pub fn moves<P: Position>(&self, opcode: &str, pos: &P) -> Result<Vec<Move>, SanError> {
+        self.operands(opcode).unwrap_or(&[]).iter().map(|operand| {
+            San::from_str(operand)?.to_move(pos)
+        }).collect()
+    }
+
+    /// The best moves (`bm`). See [`moves()`](#method.moves).
+    pub fn bm<P: Position>(&self, pos: &P) -> Result<Vec<Move>, SanError> {
+        self.moves("bm", pos)
+    }
+
+    /// The moves to avoid (`am`). See [`moves()`](#method.moves).
+    pub fn am<P: Position>(&self, pos: &P) -> Result<Vec<Move>, SanError> {
+        self.moves("am", pos)
+    }
+
+    /// The predicted variation (`pv`), resolved move by move starting from
+    /// `pos`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanError`] if an operand is not a legal SAN.
+    ///
+    /// [`SanError`]: ../san/enum.SanError.html
+    pub fn pv<P: Position + Clone>(&self, pos: &P) -> Result<Vec<Move>, SanError> {
+        let mut pos = pos.clone();
+        self.operands("pv").unwrap_or(&[]).iter().map(|operand| {
+            let m = San::from_str(operand)?.to_move(&pos)?;
+            pos.play_unchecked(&m);
+            Ok(m)
+        }).collect()
+    }
+}
+
+impl FromStr for Epd {
+    type Err = EpdError;
+
+    fn from_str(epd: &str) -> Result<Epd, EpdError> {
+        Epd::from_bytes(epd.as_bytes())
+    }
+}
+
+impl fmt::Display for Epd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&fen::epd(&self.fen, &FenOpts::default()))?;
+
+        for op in &self.operations {
+            write!(f, " {}", op.opcode)?;
+
+            let quote = op.opcode == "id" || is_comment(&op.opcode);
+
+            for operand in &op.operands {
+                if quote || operand.is_empty() || operand.contains(|ch: char| ch.is_whitespace() || ch == ';' || ch == '"') {
+                    write!(f, " \"{}\"", operand.replace('\\', "\\\\").replace('"', "\\\""))?;
+                } else {
+                    write!(f, " {}", operand)?;
+                }
+            }
+
+            f.write_str(";")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use square::Square;
+    use types::Color;
+    use position::Chess;
+
+    #[test]
+    fn test_operations() {
+        let epd: Epd = "r1bq1rk1/pp2ppbp/2np1np1/8/3NP3/2N1BP2/PPPQ2PP/R3KB1R w KQ - hmvc 3; fmvn 9; bm g4 O-O-O; ce 35; acd 12; c0 \"Yugoslav; attack\"; pv Bc4 Bd7 O-O-O;"
+            .parse().expect("valid epd");
+
+        assert_eq!(epd.fen.halfmove_clock, 3);
+        assert_eq!(epd.fen.fullmoves, 9);
+        assert_eq!(epd.ce(), Some(35));
+        assert_eq!(epd.acd(), Some(12));
+        assert_eq!(epd.dm(), None);
+        assert_eq!(epd.comment(0), Some("Yugoslav; attack"));
+
+        let pos: Chess = epd.position().expect("legal position");
+        let bm = epd.bm(&pos).expect("legal moves");
+        assert_eq!(bm.len(), 2);
+        assert_eq!(bm[1], Move::Castle { king: Square::E1, rook: Square::A1 });
+        assert_eq!(epd.am(&pos).expect("no am"), vec![]);
+        assert_eq!(epd.pv(&pos).expect("legal pv").len(), 3);
+
+        let written = epd.to_string();
+        assert_eq!(written.parse::<Epd>().expect("roundtrip"), epd);
+        assert!(written.contains(" c0 \"Yugoslav; attack\";"));
+    }
+
+    #[test]
+    fn test_whitespace() {
+        let epd: Epd = "  4k3/8/8/8/8/8/8/4K3  b \t -   -  bm Kd7; id \"spaces\";\n"
+            .parse().expect("valid epd");
+        assert_eq!(epd.fen.turn, Color::Black);
+        assert_eq!(epd.id(), Some("spaces"));
+        assert_eq!(epd.to_string(), "4k3/8/8/8/8/8/8/4K3 b - - bm Kd7; id \"spaces\";");
+    }
+
+    #[test]
+    fn test_set() {
+        let mut epd = Epd::new(Fen::default());
+        epd.set("id", vec!["start".to_owned()]);
+        epd.set("bm", vec!["e4".to_owned(), "d4".to_owned()]);
+        epd.set("id", vec!["startpos".to_owned()]);
+        assert_eq!(epd.to_string(), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - id \"startpos\"; bm e4 d4;");
+
+        epd.remove("bm");
+        assert_eq!(epd.operands("bm"), None);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!("8/8/8/8/8/8/8/8 w - - id \"unterminated;".parse::<Epd>().is_err());
+        assert!("8/8/8/8/8/8/8/8 w - - bm e4".parse::<Epd>().is_err());
+        assert!("8/8/8/8/8/8/8/8 w - - 1bm e4;".parse::<Epd>().is_err());
+        assert!("8/8/8/8/8/8/8 w - - bm e4;".parse::<Epd>().is_err());
+        assert_eq!("8/8/8/8/8/8/8/8 w -".parse::<Epd>(), Err(EpdError::MissingFields));
+        assert_eq!("8/8/8/8/8/8/8/8 w - ".parse::<Epd>(), Err(EpdError::MissingFields));
+
+        let epd: Epd = "4k3/8/8/8/8/8/8/4K3 w - - bm Qh5;".parse().expect("valid epd");
+        let pos: Chess = epd.position().expect("legal position");
+        assert!(epd.bm(&pos).is_err());
+    }
+}
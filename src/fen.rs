@@ -71,7 +71,9 @@
 //! [`Fen`]: struct.Fen.html
 //! [`Display`]: https://doc.rust-lang.org/std/fmt/trait.Display.html
 
+use std::str;
 use std::str::FromStr;
+use std::cmp;
 use std::fmt;
 use std::char;
 use std::error::Error;
@@ -137,38 +139,97 @@ impl Default for FenOpts {
 }
 
 /// Errors that can occur when parsing a FEN.
-#[derive(Eq, PartialEq, Debug)]
+///
+/// Each error carries the byte offset in the input where the problem was
+/// detected.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum FenError {
-    InvalidFen,
-    InvalidBoard,
-    InvalidPocket,
-    InvalidTurn,
-    InvalidCastling,
-    InvalidEpSquare,
-    InvalidRemainingChecks,
-    InvalidHalfmoveClock,
-    InvalidFullmoves,
+    /// There are unexpected parts after the fullmove number.
+    InvalidFen { offset: usize },
+    /// Unexpected character in the board part.
+    InvalidBoard { offset: usize, ch: char },
+    /// A rank has more than 8 files.
+    TooManyFiles { offset: usize },
+    /// A rank has less than 8 files.
+    TooFewFiles { offset: usize },
+    /// The board part has more than 8 ranks.
+    TooManyRanks { offset: usize },
+    /// The board part has less than 8 ranks.
+    TooFewRanks { offset: usize },
+    /// Unexpected character in the pockets.
+    InvalidPocket { offset: usize, ch: char },
+    /// The turn part is neither `w` nor `b`.
+    InvalidTurn { offset: usize },
+    /// Unexpected character in the castling part.
+    InvalidCastling { offset: usize, ch: char },
+    /// Castling rights for which there is no rook on the back rank.
+    CastlingWithoutRook { offset: usize, ch: char },
+    /// The en passant part is neither `-` nor a square.
+    InvalidEpSquare { offset: usize },
+    /// The remaining checks part is not of the form `3+3`.
+    InvalidRemainingChecks { offset: usize },
+    /// The halfmove clock is not a non-negative number.
+    InvalidHalfmoveClock { offset: usize },
+    /// The fullmove number is not a non-negative number.
+    InvalidFullmoves { offset: usize },
+    /// A number that is too large, e.g. a move counter that does not fit
+    /// into a `u32`.
+    CounterOverflow { offset: usize },
 }
 
 impl FenError {
+    /// The byte offset of the error in the input.
+    pub fn offset(&self) -> usize {
+        match *self {
+            FenError::InvalidFen { offset } |
+            FenError::InvalidBoard { offset, .. } |
+            FenError::TooManyFiles { offset } |
+            FenError::TooFewFiles { offset } |
+            FenError::TooManyRanks { offset } |
+            FenError::TooFewRanks { offset } |
+            FenError::InvalidPocket { offset, .. } |
+            FenError::InvalidTurn { offset } |
+            FenError::InvalidCastling { offset, .. } |
+            FenError::CastlingWithoutRook { offset, .. } |
+            FenError::InvalidEpSquare { offset } |
+            FenError::InvalidRemainingChecks { offset } |
+            FenError::InvalidHalfmoveClock { offset } |
+            FenError::InvalidFullmoves { offset } |
+            FenError::CounterOverflow { offset } => offset,
+        }
+    }
+
     fn desc(&self) -> &str {
         match *self {
-            FenError::InvalidFen => "invalid fen",
-            FenError::InvalidBoard => "invalid board part in fen",
-            FenError::InvalidPocket => "invalid pocket in fen",
-            FenError::InvalidTurn => "invalid turn part in fen",
-            FenError::InvalidCastling => "invalid castling part in fen",
-            FenError::InvalidEpSquare => "invalid ep square in fen",
-            FenError::InvalidRemainingChecks => "invalid remaining checks in fen",
-            FenError::InvalidHalfmoveClock => "invalid halfmove clock in fen",
-            FenError::InvalidFullmoves => "invalid fullmove part in fen",
+            FenError::InvalidFen { .. } => "invalid fen",
+            FenError::InvalidBoard { .. } => "invalid board part in fen",
+            FenError::TooManyFiles { .. } => "too many files in rank in fen",
+            FenError::TooFewFiles { .. } => "too few files in rank in fen",
+            FenError::TooManyRanks { .. } => "too many ranks in fen",
+            FenError::TooFewRanks { .. } => "too few ranks in fen",
+            FenError::InvalidPocket { .. } => "invalid pocket in fen",
+            FenError::InvalidTurn { .. } => "invalid turn part in fen",
+            FenError::InvalidCastling { .. } => "invalid castling part in fen",
+            FenError::CastlingWithoutRook { .. } => "castling rights without rook in fen",
+            FenError::InvalidEpSquare { .. } => "invalid ep square in fen",
+            FenError::InvalidRemainingChecks { .. } => "invalid remaining checks in fen",
+            FenError::InvalidHalfmoveClock { .. } => "invalid halfmove clock in fen",
+            FenError::InvalidFullmoves { .. } => "invalid fullmove part in fen",
+            FenError::CounterOverflow { .. } => "counter overflow in fen",
         }
     }
 }
 
 impl fmt::Display for FenError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.desc().fmt(f)
+        match *self {
+            FenError::InvalidBoard { ch, .. } |
+            FenError::InvalidPocket { ch, .. } |
+            FenError::InvalidCastling { ch, .. } |
+            FenError::CastlingWithoutRook { ch, .. } =>
+                write!(f, "{} ({:?} at byte {})", self.desc(), ch, self.offset()),
+            _ => write!(f, "{} (at byte {})", self.desc(), self.offset()),
+        }
     }
 }
 
@@ -178,6 +239,26 @@ impl Error for FenError {
     }
 }
 
+/// Decodes the (possibly non-ASCII) character starting at `offset`.
+fn char_at(fen: &[u8], offset: usize) -> char {
+    let end = cmp::min(offset + 4, fen.len());
+    let bytes = &fen[offset..end];
+    let valid = match str::from_utf8(bytes) {
+        Ok(valid) => valid,
+        Err(err) => str::from_utf8(&bytes[..err.valid_up_to()]).expect("valid prefix"),
+    };
+    valid.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// Parses a move counter or number of remaining checks.
+fn parse_counter(part: &[u8], offset: usize, invalid: FenError) -> Result<u32, FenError> {
+    if !part.is_empty() && part.iter().all(|ch| ch.is_ascii_digit()) {
+        btoi::btou(part).map_err(|_| FenError::CounterOverflow { offset })
+    } else {
+        Err(invalid)
+    }
+}
+
 impl Board {
     fn from_board_fen(board_fen: &[u8]) -> Result<Board, FenError> {
         let mut board = Board::empty();
@@ -186,8 +267,13 @@ impl Board {
         let mut file = 0i8;
         let mut promoted = false;
 
-        for &ch in board_fen {
-            if ch == b'/' && file == 8 {
+        for (offset, &ch) in board_fen.iter().enumerate() {
+            if ch == b'/' {
+                if file < 8 {
+                    return Err(FenError::TooFewFiles { offset });
+                } else if rank == 0 {
+                    return Err(FenError::TooManyRanks { offset });
+                }
                 file = 0;
                 rank -= 1;
             } else if ch == b'~' {
@@ -196,7 +282,7 @@ impl Board {
             } else if b'1' <= ch && ch <= b'8' {
                 file += (ch - b'0') as i8;
                 if file > 8 {
-                    return Err(FenError::InvalidBoard);
+                    return Err(FenError::TooManyFiles { offset });
                 }
             } else if let Some(piece) = Piece::from_char(ch as char) {
                 match Square::from_coords(file, rank) {
@@ -204,22 +290,27 @@ impl Board {
                         board.set_piece_at(sq, piece, promoted);
                         promoted = false;
                     }
-                    None => return Err(FenError::InvalidBoard),
+                    None => return Err(FenError::TooManyFiles { offset }),
                 }
                 file += 1;
             } else {
-                return Err(FenError::InvalidBoard);
+                return Err(FenError::InvalidBoard { offset, ch: char_at(board_fen, offset) });
             }
 
             if promoted {
-                return Err(FenError::InvalidBoard);
+                // The promotion marker must be followed by a piece.
+                return Err(FenError::InvalidBoard { offset, ch: char_at(board_fen, offset) });
             }
         }
 
-        if rank == 0 && file == 8 {
-            Ok(board)
+        if promoted {
+            Err(FenError::InvalidBoard { offset: board_fen.len() - 1, ch: '~' })
+        } else if file < 8 {
+            Err(FenError::TooFewFiles { offset: board_fen.len() })
+        } else if rank > 0 {
+            Err(FenError::TooFewRanks { offset: board_fen.len() })
         } else {
-            Err(FenError::InvalidBoard)
+            Ok(board)
         }
     }
 }
@@ -326,18 +417,26 @@ impl Fen {
     ///
     /// [`FenError`]: enum.FenError.html
     pub fn from_bytes(fen: &[u8]) -> Result<Fen, FenError> {
-        let mut parts = fen.split(|ch| *ch == b' ');
+        let mut end = 0;
+        let mut parts = fen.split(|ch| *ch == b' ').map(|part| {
+            let offset = end;
+            end += part.len() + 1;
+            (offset, part)
+        });
         let mut result = Fen::empty();
 
-        let board_part = parts.next().expect("splits have at least one part");
+        let (_, board_part) = parts.next().expect("splits have at least one part");
 
         let (board_part, pockets) = if board_part.ends_with(b"]") {
             let split_point = board_part
                 .iter().position(|ch| *ch == b'[')
-                .ok_or(FenError::InvalidBoard)?;
+                .ok_or(FenError::InvalidBoard { offset: board_part.len() - 1, ch: ']' })?;
             let mut pockets = Pockets::default();
-            for &ch in &board_part[(split_point + 1)..(board_part.len() - 1)] {
-                pockets.add(Piece::from_char(ch as char).ok_or(FenError::InvalidPocket)?);
+            for (i, &ch) in board_part[(split_point + 1)..(board_part.len() - 1)].iter().enumerate() {
+                let offset = split_point + 1 + i;
+                pockets.add(Piece::from_char(ch as char).ok_or_else(|| {
+                    FenError::InvalidPocket { offset, ch: char_at(fen, offset) }
+                })?);
             }
             (&board_part[..split_point], Some(pockets))
         } else {
@@ -348,15 +447,16 @@ impl Fen {
         result.pockets = pockets;
 
         result.turn = match parts.next() {
-            Some(b"w") | None => White,
-            Some(b"b") => Black,
-            Some(_) => return Err(FenError::InvalidTurn),
+            Some((_, b"w")) | None => White,
+            Some((_, b"b")) => Black,
+            Some((offset, _)) => return Err(FenError::InvalidTurn { offset }),
         };
 
         match parts.next() {
-            Some(b"-") | None => (),
-            Some(castling_part) => {
-                for &ch in castling_part {
+            Some((_, b"-")) | None => (),
+            Some((start, castling_part)) => {
+                for (i, &ch) in castling_part.iter().enumerate() {
+                    let offset = start + i;
                     let color = Color::from_bool(ch < b'a'); // uppercase
 
                     let candidates = Bitboard::relative_rank(color, 0) &
@@ -368,50 +468,61 @@ impl Fen {
                         file @ b'a' ... b'h' => {
                             (candidates & Bitboard::file((file as u8 - b'a') as i8)).first()
                         },
-                        _ => return Err(FenError::InvalidCastling),
+                        _ => return Err(FenError::InvalidCastling { offset, ch: char_at(fen, offset) }),
                     };
 
-                    result.castling_rights.add(flag.ok_or(FenError::InvalidCastling)?);
+                    result.castling_rights.add(flag.ok_or(FenError::CastlingWithoutRook {
+                        offset,
+                        ch: ch as char,
+                    })?);
                 }
             }
         }
 
         match parts.next() {
-            Some(b"-") | None => (),
-            Some(ep_part) => {
+            Some((_, b"-")) | None => (),
+            Some((offset, ep_part)) => {
                 result.ep_square =
                     Some(Square::from_bytes(ep_part)
-                            .map_err(|_| FenError::InvalidEpSquare)?);
+                            .map_err(|_| FenError::InvalidEpSquare { offset })?);
             },
         }
 
-        let halfmoves_part = if let Some(checks_part) = parts.next() {
+        let halfmoves_part = if let Some((offset, checks_part)) = parts.next() {
             let mut checks = checks_part.splitn(2, |ch| *ch == b'+');
             if let (Some(w), Some(b)) = (checks.next(), checks.next()) {
+                let invalid = FenError::InvalidRemainingChecks { offset };
+                let white = parse_counter(w, offset, invalid)?;
+                let black = parse_counter(b, offset + w.len() + 1, invalid)?;
+                if white > u32::from(u8::MAX) {
+                    return Err(FenError::CounterOverflow { offset });
+                } else if black > u32::from(u8::MAX) {
+                    return Err(FenError::CounterOverflow { offset: offset + w.len() + 1 });
+                }
                 result.remaining_checks = Some(RemainingChecks {
-                    white: btoi::btou(w).map_err(|_| FenError::InvalidRemainingChecks)?,
-                    black: btoi::btou(b).map_err(|_| FenError::InvalidRemainingChecks)?,
+                    white: white as u8,
+                    black: black as u8,
                 });
                 parts.next()
             } else {
-                Some(checks_part)
+                Some((offset, checks_part))
             }
         } else {
             None
         };
 
-        if let Some(halfmoves_part) = halfmoves_part {
-            result.halfmove_clock = btoi::btou_saturating(halfmoves_part)
-                .map_err(|_| FenError::InvalidHalfmoveClock)?;
+        if let Some((offset, halfmoves_part)) = halfmoves_part {
+            result.halfmove_clock = parse_counter(halfmoves_part, offset,
+                                                  FenError::InvalidHalfmoveClock { offset })?;
         }
 
-        if let Some(fullmoves_part) = parts.next() {
-            result.fullmoves = btoi::btou_saturating(fullmoves_part)
-                .map_err(|_| FenError::InvalidFullmoves)?;
+        if let Some((offset, fullmoves_part)) = parts.next() {
+            result.fullmoves = parse_counter(fullmoves_part, offset,
+                                             FenError::InvalidFullmoves { offset })?;
         }
 
-        if parts.next().is_some() {
-            Err(FenError::InvalidFen)
+        if let Some((offset, _)) = parts.next() {
+            Err(FenError::InvalidFen { offset })
         } else {
             Ok(result)
         }
@@ -559,11 +670,45 @@ mod tests {
         assert_eq!(fen.fullmoves, 42);
     }
 
+    #[test]
+    fn test_error_offsets() {
+        for &(fen, err) in &[
+            ("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR", FenError::InvalidBoard { offset: 18, ch: '9' }),
+            ("rnbqkbnr/ppppXppp/8/8/8/8/PPPPPPPP/RNBQKBNR", FenError::InvalidBoard { offset: 13, ch: 'X' }),
+            ("rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", FenError::TooManyFiles { offset: 17 }),
+            ("rnbqkbnr/pppppppp/45/8/8/8/PPPPPPPP/RNBQKBNR", FenError::TooManyFiles { offset: 19 }),
+            ("rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR", FenError::TooFewFiles { offset: 16 }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN", FenError::TooFewFiles { offset: 42 }),
+            ("rnbqkbnr/pppppppp/8/8/8/8/8/PPPPPPPP/RNBQKBNR", FenError::TooManyRanks { offset: 36 }),
+            ("rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR", FenError::TooFewRanks { offset: 41 }),
+            ("8/8/8/8/8/8/8/8~ w - - 0 1", FenError::InvalidBoard { offset: 15, ch: '~' }),
+            ("8/8/8/8/8/8/8/8[QX] w - - 0 1", FenError::InvalidPocket { offset: 17, ch: 'X' }),
+            ("8/8/8/8/8/8/8/8 x - - 0 1", FenError::InvalidTurn { offset: 16 }),
+            ("4k3/8/8/8/8/8/8/R3K3 w Qk - 0 1", FenError::CastlingWithoutRook { offset: 24, ch: 'k' }),
+            ("8/8/8/8/8/8/8/8 w - e9 0 1", FenError::InvalidEpSquare { offset: 20 }),
+            ("8/8/8/8/8/8/8/8 w - - 3+x 0 1", FenError::InvalidRemainingChecks { offset: 22 }),
+            ("8/8/8/8/8/8/8/8 w - - 3+256 0 1", FenError::CounterOverflow { offset: 24 }),
+            ("8/8/8/8/8/8/8/8 w - - -1 1", FenError::InvalidHalfmoveClock { offset: 22 }),
+            ("8/8/8/8/8/8/8/8 w - - 0 99999999999", FenError::CounterOverflow { offset: 24 }),
+            ("8/8/8/8/8/8/8/8 w - - 4294967296 1", FenError::CounterOverflow { offset: 22 }),
+            ("8/8/8/8/8/8/8/8 w - - 0 1 x", FenError::InvalidFen { offset: 26 }),
+        ] {
+            assert_eq!(fen.parse::<Fen>(), Err(err), "{}", fen);
+        }
+
+        let fen: Fen = "8/8/8/8/8/8/8/8 w - - 4294967295 4294967295".parse().expect("largest counters");
+        assert_eq!(fen.halfmove_clock, 4294967295);
+        assert_eq!(fen.fullmoves, 4294967295);
+
+        let err = "4k3/8/8/8/8/8/8/R3K3 w Qk - 0 1".parse::<Fen>().expect_err("no rook");
+        assert_eq!(err.to_string(), "castling rights without rook in fen ('k' at byte 24)");
+    }
+
     #[test]
     fn test_non_ascii() {
         // mind the dot in the castling part
         let input = "8/8/8/8/8/8/8/8 w · - 0 1";
         let error = input.parse::<Fen>().expect_err("invalid fen");
-        assert_eq!(error, FenError::InvalidCastling);
+        assert_eq!(error, FenError::InvalidCastling { offset: 18, ch: '·' });
     }
 }
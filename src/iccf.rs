@@ -0,0 +1,236 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Parse and write moves in ICCF numeric notation.
+//!
+//! Files and ranks are numbered from `1` to `8`, so `5254` is `e2e4`.
+//! Promotions append a digit for the piece: `1` for a queen, `2` for a
+//! rook, `3` for a bishop and `4` for a knight. Castling is written as the
+//! king move, e.g. `5171`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use shakmaty::{Chess, Square};
+//! use shakmaty::iccf::Iccf;
+//!
+//! let iccf: Iccf = "5254".parse()?;
+//! assert_eq!(iccf, Iccf { from: Square::E2, to: Square::E4, promotion: None });
+//!
+//! let pos = Chess::default();
+//! let m = iccf.to_move(&pos)?;
+//! assert_eq!(Iccf::from_move(&m).map(|iccf| iccf.to_string()), Some("5254".to_owned()));
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::error::Error;
+
+use square::Square;
+use types::{Role, Move};
+use setup::CastlingMode;
+use position::{Position, IllegalMove};
+use uci::Uci;
+
+/// Error when parsing an invalid ICCF numeric move.
+pub struct InvalidIccf {
+    _priv: (),
+}
+
+impl fmt::Debug for InvalidIccf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InvalidIccf").finish()
+    }
+}
+
+impl fmt::Display for InvalidIccf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "invalid iccf".fmt(f)
+    }
+}
+
+impl Error for InvalidIccf {
+    fn description(&self) -> &str {
+        "invalid iccf"
+    }
+}
+
+impl From<()> for InvalidIccf {
+    fn from(_: ()) -> InvalidIccf {
+        InvalidIccf { _priv: () }
+    }
+}
+
+/// A move in ICCF numeric notation.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Iccf {
+    pub from: Square,
+    pub to: Square,
+    pub promotion: Option<Role>,
+}
+
+fn square_from_digits(file: u8, rank: u8) -> Option<Square> {
+    Square::from_coords(file.wrapping_sub(b'1') as i8, rank.wrapping_sub(b'1') as i8)
+}
+
+fn promotion_from_digit(digit: u8) -> Option<Role> {
+    match digit {
+        b'1' => Some(Role::Queen),
+        b'2' => Some(Role::Rook),
+        b'3' => Some(Role::Bishop),
+        b'4' => Some(Role::Knight),
+        _ => None,
+    }
+}
+
+fn promotion_digit(role: Role) -> Option<char> {
+    match role {
+        Role::Queen => Some('1'),
+        Role::Rook => Some('2'),
+        Role::Bishop => Some('3'),
+        Role::Knight => Some('4'),
+        _ => None,
+    }
+}
+
+impl Iccf {
+    /// Parses a move in ICCF numeric notation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidIccf`] if the input is not syntactically valid.
+    ///
+    /// [`InvalidIccf`]: struct.InvalidIccf.html
+    pub fn from_bytes(iccf: &[u8]) -> Result<Iccf, InvalidIccf> {
+        if iccf.len() != 4 && iccf.len() != 5 {
+            return Err(InvalidIccf { _priv: () });
+        }
+
+        Ok(Iccf {
+            from: square_from_digits(iccf[0], iccf[1]).ok_or(())?,
+            to: square_from_digits(iccf[2], iccf[3]).ok_or(())?,
+            promotion: match iccf.get(4) {
+                Some(&digit) => Some(promotion_from_digit(digit).ok_or(())?),
+                None => None,
+            },
+        })
+    }
+
+    /// Converts a move to ICCF numeric notation. Castling moves are written
+    /// as king moves, or as the king moving onto its own rook if the king
+    /// and rook are not on their standard squares.
+    ///
+    /// Returns `None` for moves that can not be represented, i.e. drops and
+    /// promotions to a king.
+    pub fn from_move(m: &Move) -> Option<Iccf> {
+        match Uci::from_move(m, CastlingMode::Standard) {
+            Uci::Normal { promotion: Some(Role::Pawn), .. } |
+            Uci::Normal { promotion: Some(Role::King), .. } => None,
+            Uci::Normal { from, to, promotion } => Some(Iccf { from, to, promotion }),
+            _ => None,
+        }
+    }
+
+    /// Tries to convert the `Iccf` to a legal [`Move`] in the context of a
+    /// position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IllegalMove`] if the move is not legal.
+    ///
+    /// [`Move`]: ../enum.Move.html
+    /// [`IllegalMove`]: ../struct.IllegalMove.html
+    pub fn to_move<P: Position>(&self, pos: &P) -> Result<Move, IllegalMove> {
+        Uci::Normal { from: self.from, to: self.to, promotion: self.promotion }
+            .to_move_with_mode(pos, CastlingMode::Standard)
+    }
+}
+
+impl FromStr for Iccf {
+    type Err = InvalidIccf;
+
+    fn from_str(iccf: &str) -> Result<Iccf, InvalidIccf> {
+        Iccf::from_bytes(iccf.as_bytes())
+    }
+}
+
+impl fmt::Display for Iccf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}{}{}",
+               self.from.file() + 1, self.from.rank() + 1,
+               self.to.file() + 1, self.to.rank() + 1)?;
+        if let Some(digit) = self.promotion.and_then(promotion_digit) {
+            write!(f, "{}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fen::Fen;
+    use position::Chess;
+
+    #[test]
+    fn test_read_write() {
+        for iccf in &["5254", "1181", "8818", "57581", "27184", "5171"] {
+            let result = iccf.parse::<Iccf>().expect("valid iccf").to_string();
+            assert_eq!(*iccf, result);
+        }
+
+        for iccf in &["", "525", "0254", "5290", "52545", "525400", "e2e4"] {
+            assert!(iccf.parse::<Iccf>().is_err(), "{} should be invalid", iccf);
+        }
+    }
+
+    #[test]
+    fn test_to_move() {
+        let fen: Fen = "r3k3/1P6/8/8/8/8/8/4K2R w Kq - 0 1".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        let castle = "5171".parse::<Iccf>().expect("valid iccf").to_move(&pos).expect("legal");
+        assert_eq!(castle, Move::Castle { king: Square::E1, rook: Square::H1 });
+        assert_eq!(Iccf::from_move(&castle).expect("representable").to_string(), "5171");
+
+        let promotion = "27184".parse::<Iccf>().expect("valid iccf").to_move(&pos).expect("legal");
+        assert_eq!(promotion.promotion(), Some(Role::Knight));
+        assert_eq!(promotion.capture(), Some(Role::Rook));
+
+        assert!("2728".parse::<Iccf>().expect("valid iccf").to_move(&pos).is_err());
+
+        let fen: Fen = "4k3/8/8/8/8/8/8/1R3KR1 w GB - 0 1".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        for &(king, rook, iccf) in &[(Square::F1, Square::G1, "6171"), (Square::F1, Square::B1, "6121")] {
+            let castle = Move::Castle { king, rook };
+            let written = Iccf::from_move(&castle).expect("representable");
+            assert_eq!(written.to_string(), iccf);
+            assert_eq!(written.to_move(&pos).expect("legal"), castle);
+        }
+        assert_eq!(Iccf::from_move(&Move::Put { role: Role::Pawn, to: Square::E4 }), None);
+    }
+}
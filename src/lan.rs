@@ -0,0 +1,384 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Read and write Long Algebraic Notation.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use shakmaty::{Chess, Square, Role, Move};
+//! use shakmaty::lan::Lan;
+//!
+//! let lan: Lan = "Ng1-f3".parse()?;
+//!
+//! let pos = Chess::default();
+//! let m = lan.to_move(&pos)?;
+//! assert_eq!(m, Move::Normal {
+//!     role: Role::Knight,
+//!     from: Square::G1,
+//!     capture: None,
+//!     to: Square::F3,
+//!     promotion: None,
+//! });
+//!
+//! assert_eq!(Lan::from(&m).to_string(), "Ng1-f3");
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::error::Error;
+
+use square::Square;
+use types::{Move, Role};
+use setup::CastlingSide;
+use bitboard::Bitboard;
+use position::{Position, IllegalMove, IllegalMoveReason, Outcome};
+use movelist::MoveList;
+
+/// Error when parsing a syntactically invalid LAN.
+#[derive(Eq, PartialEq)]
+pub struct InvalidLan {
+    _priv: (),
+}
+
+impl fmt::Debug for InvalidLan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InvalidLan").finish()
+    }
+}
+
+impl fmt::Display for InvalidLan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "invalid lan".fmt(f)
+    }
+}
+
+impl Error for InvalidLan {
+    fn description(&self) -> &str {
+        "invalid lan"
+    }
+}
+
+impl From<()> for InvalidLan {
+    fn from(_: ()) -> InvalidLan {
+        InvalidLan { _priv: () }
+    }
+}
+
+/// A move in Long Algebraic Notation.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Lan {
+    Normal {
+        role: Role,
+        from: Square,
+        capture: bool,
+        to: Square,
+        promotion: Option<Role>,
+    },
+    Castle(CastlingSide),
+    Put { role: Role, to: Square },
+    Null,
+}
+
+impl Lan {
+    /// Parses a LAN. Ignores a possible check or checkmate suffix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidLan`] if `lan` is not syntactically valid.
+    ///
+    /// [`InvalidLan`]: struct.InvalidLan.html
+    pub fn from_bytes(mut lan: &[u8]) -> Result<Lan, InvalidLan> {
+        if lan.ends_with(b"#") || lan.ends_with(b"+") {
+            lan = &lan[0..(lan.len() - 1)];
+        }
+
+        if lan == b"--" {
+            return Ok(Lan::Null);
+        } else if lan == b"O-O" {
+            return Ok(Lan::Castle(CastlingSide::KingSide));
+        } else if lan == b"O-O-O" {
+            return Ok(Lan::Castle(CastlingSide::QueenSide));
+        }
+
+        let (role, lan) = match lan.first() {
+            Some(&ch) if ch.is_ascii_uppercase() => (Role::from_char(ch as char).ok_or(())?, &lan[1..]),
+            _ => (Role::Pawn, lan),
+        };
+
+        if lan.len() == 3 && lan[0] == b'@' {
+            return Ok(Lan::Put { role, to: Square::from_bytes(&lan[1..]).map_err(|_| ())? });
+        }
+
+        if lan.len() < 5 {
+            return Err(InvalidLan { _priv: () });
+        }
+
+        let from = Square::from_bytes(&lan[0..2]).map_err(|_| ())?;
+        let capture = match lan[2] {
+            b'-' => false,
+            b'x' => true,
+            _ => return Err(InvalidLan { _priv: () }),
+        };
+        let to = Square::from_bytes(&lan[3..5]).map_err(|_| ())?;
+
+        let promotion = match &lan[5..] {
+            b"" => None,
+            &[b'=', ch] if role == Role::Pawn && ch.is_ascii_uppercase() =>
+                Some(Role::from_char(ch as char).ok_or(())?),
+            _ => return Err(InvalidLan { _priv: () }),
+        };
+
+        Ok(Lan::Normal { role, from, capture, to, promotion })
+    }
+
+    /// Tries to convert the `Lan` to a legal move in the context of a
+    /// position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IllegalMove`] if there is no matching legal move. This
+    /// includes moves with a wrong piece or capture indicator, which are
+    /// reported as [`IllegalMoveReason::Mismatch`].
+    ///
+    /// [`IllegalMoveReason::Mismatch`]: ../enum.IllegalMoveReason.html#variant.Mismatch
+    ///
+    /// [`IllegalMove`]: ../struct.IllegalMove.html
+    pub fn to_move<P: Position>(&self, pos: &P) -> Result<Move, IllegalMove> {
+        let mut legals = MoveList::new();
+
+        match *self {
+            Lan::Normal { role, from, capture, to, promotion } => {
+                pos.san_candidates(role, to, &mut legals);
+                legals.retain(|m| match *m {
+                    Move::Normal { from: f, capture: c, promotion: p, .. } =>
+                        from == f && capture == c.is_some() && promotion == p,
+                    Move::EnPassant { from: f, .. } =>
+                        from == f && capture && promotion.is_none(),
+                    _ => false,
+                });
+            },
+            Lan::Castle(side) => pos.castling_moves(side, &mut legals),
+            Lan::Put { role, to } => {
+                pos.san_candidates(role, to, &mut legals);
+                legals.retain(|m| match *m {
+                    Move::Put { .. } => true,
+                    _ => false,
+                });
+            },
+            Lan::Null => return Err(IllegalMove { reason: IllegalMoveReason::Other }),
+        }
+
+        legals.first().cloned().ok_or_else(|| self.explain(pos))
+    }
+
+    fn explain<P: Position>(&self, pos: &P) -> IllegalMove {
+        let reason = match *self {
+            Lan::Normal { role, from, to, promotion, .. } => {
+                let m = Move::Normal { role, from, capture: pos.board().role_at(to), to, promotion };
+                if pos.is_legal(&m) {
+                    // Only the capture indicator is wrong.
+                    IllegalMoveReason::Mismatch
+                } else {
+                    return IllegalMove::explain(pos, &m);
+                }
+            },
+            Lan::Castle(side) => {
+                let turn = pos.turn();
+                let rooks = pos.castling_rights() & pos.us() & Bitboard::relative_rank(turn, 0);
+                let rook = match side {
+                    CastlingSide::KingSide => rooks.last(),
+                    CastlingSide::QueenSide => rooks.first(),
+                };
+                match (pos.board().king_of(turn), rook) {
+                    (Some(king), Some(rook)) => return IllegalMove::explain(pos, &Move::Castle { king, rook }),
+                    _ => IllegalMoveReason::NoCastlingRights,
+                }
+            },
+            Lan::Put { .. } | Lan::Null => IllegalMoveReason::Other,
+        };
+
+        IllegalMove { reason }
+    }
+}
+
+impl<'a> From<&'a Move> for Lan {
+    fn from(m: &'a Move) -> Lan {
+        match *m {
+            Move::Normal { role, from, capture, to, promotion } =>
+                Lan::Normal { role, from, capture: capture.is_some(), to, promotion },
+            Move::EnPassant { from, to } =>
+                Lan::Normal { role: Role::Pawn, from, capture: true, to, promotion: None },
+            Move::Castle { king, rook } if rook < king => Lan::Castle(CastlingSide::QueenSide),
+            Move::Castle { .. } => Lan::Castle(CastlingSide::KingSide),
+            Move::Put { role, to } => Lan::Put { role, to },
+        }
+    }
+}
+
+impl From<Move> for Lan {
+    fn from(m: Move) -> Lan {
+        Lan::from(&m)
+    }
+}
+
+impl FromStr for Lan {
+    type Err = InvalidLan;
+
+    fn from_str(lan: &str) -> Result<Lan, InvalidLan> {
+        Lan::from_bytes(lan.as_bytes())
+    }
+}
+
+impl fmt::Display for Lan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Lan::Normal { role, from, capture, to, promotion } => {
+                if role != Role::Pawn {
+                    write!(f, "{}", (32 ^ role.char() as u8) as char)?;
+                }
+                write!(f, "{}{}{}", from, if capture { 'x' } else { '-' }, to)?;
+                if let Some(promotion) = promotion {
+                    write!(f, "={}", (32 ^ promotion.char() as u8) as char)?;
+                }
+                Ok(())
+            },
+            Lan::Castle(CastlingSide::KingSide) => write!(f, "O-O"),
+            Lan::Castle(CastlingSide::QueenSide) => write!(f, "O-O-O"),
+            Lan::Put { role: Role::Pawn, to } => write!(f, "@{}", to),
+            Lan::Put { role, to } => write!(f, "{}@{}", (32 ^ role.char() as u8) as char, to),
+            Lan::Null => write!(f, "--"),
+        }
+    }
+}
+
+/// A [`Lan`] and possible check and checkmate suffixes.
+///
+/// [`Lan`]: enum.Lan.html
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LanPlus {
+    pub lan: Lan,
+    pub check: bool,
+    pub checkmate: bool,
+}
+
+impl LanPlus {
+    /// Parses a LAN and possible check and checkmate suffix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidLan`] if `lan` is not syntactically valid.
+    ///
+    /// [`InvalidLan`]: struct.InvalidLan.html
+    pub fn from_bytes(lan: &[u8]) -> Result<LanPlus, InvalidLan> {
+        Lan::from_bytes(lan).map(|result| {
+            LanPlus {
+                lan: result,
+                checkmate: lan.ends_with(b"#"),
+                check: lan.ends_with(b"+"),
+            }
+        })
+    }
+}
+
+impl FromStr for LanPlus {
+    type Err = InvalidLan;
+
+    fn from_str(lan: &str) -> Result<LanPlus, InvalidLan> {
+        LanPlus::from_bytes(lan.as_bytes())
+    }
+}
+
+impl fmt::Display for LanPlus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.checkmate {
+            write!(f, "{}#", self.lan)
+        } else if self.check {
+            write!(f, "{}+", self.lan)
+        } else {
+            write!(f, "{}", self.lan)
+        }
+    }
+}
+
+/// Converts a move to Long Algebraic Notation including possible check and
+/// checkmate suffixes.
+pub fn lan_plus<P: Position>(mut pos: P, m: &Move) -> LanPlus {
+    let lan = Lan::from(m);
+    pos.play_unchecked(m);
+    let checkmate = match pos.outcome() {
+        Some(Outcome::Decisive { .. }) => true,
+        _ => false,
+    };
+    LanPlus { lan, checkmate, check: !checkmate && pos.checkers().any() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fen::Fen;
+    use position::Chess;
+
+    #[test]
+    fn test_read_write() {
+        for lan in &["e2-e4", "Ng1-f3", "Bf1xb5+", "e7xd8=Q", "a2-a1=N#",
+                     "O-O", "O-O-O+", "Q@h5", "@e4", "--", "Ke1-d1"] {
+            let result = lan.parse::<LanPlus>().expect("valid lan").to_string();
+            assert_eq!(*lan, result, "read {} write {}", lan, result);
+        }
+
+        for lan in &["", "e4", "Ng1f3", "e2-e4=", "Ng1-f3=Q", "e7-e8=q", "Xe2-e4", "e2-e9"] {
+            assert!(lan.parse::<Lan>().is_err(), "{} should be invalid", lan);
+        }
+    }
+
+    #[test]
+    fn test_to_move() {
+        let fen: Fen = "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        let m = "e5xf6".parse::<Lan>().expect("valid lan").to_move(&pos).expect("legal");
+        assert_eq!(m, Move::EnPassant { from: Square::E5, to: Square::F6 });
+
+        assert!("e5-f6".parse::<Lan>().expect("valid lan").to_move(&pos).is_err());
+        assert!("Bf1-c4".parse::<Lan>().expect("valid lan").to_move(&pos).is_ok());
+        assert!("Nf1-c4".parse::<Lan>().expect("valid lan").to_move(&pos).is_err());
+
+        let reason = |lan: &str| lan.parse::<Lan>().expect("valid lan").to_move(&pos).expect_err("illegal").reason;
+        assert_eq!(reason("Nf1-c4"), IllegalMoveReason::Mismatch);
+        assert_eq!(reason("Bf1xc4"), IllegalMoveReason::Mismatch);
+        assert_eq!(reason("Nb1-b3"), IllegalMoveReason::InvalidMovement);
+        assert_eq!(reason("O-O"), IllegalMoveReason::PathBlocked);
+    }
+
+    #[test]
+    fn test_lan_plus() {
+        let fen: Fen = "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+        let m = "Qd8-h4".parse::<Lan>().expect("valid lan").to_move(&pos).expect("legal");
+        assert_eq!(lan_plus(pos, &m).to_string(), "Qd8-h4#");
+    }
+}
@@ -85,6 +85,12 @@ extern crate arrayvec;
 extern crate bitflags;
 extern crate option_filter;
 extern crate btoi;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
 
 mod square;
 mod types;
@@ -94,18 +100,35 @@ mod position;
 mod setup;
 mod movelist;
 mod magics;
-mod perft;
+mod zobrist;
+mod chess960;
+#[cfg(feature = "serde")]
+mod serialize;
 
 pub mod attacks;
+pub mod perft;
 pub mod fen;
+pub mod epd;
 pub mod uci;
 pub mod san;
+pub mod lan;
+pub mod iccf;
+pub mod material;
+pub mod pawns;
+pub mod packed;
+pub mod render;
+#[cfg(feature = "svg")]
+pub mod svg;
+#[cfg(feature = "serde")]
+pub mod structured;
 
 pub use square::Square;
 pub use types::{Color, Role, Piece, Move, Pocket, Pockets, RemainingChecks};
 pub use bitboard::{Bitboard, CarryRippler};
 pub use board::{Board, Pieces};
-pub use setup::{Setup, CastlingSide};
+pub use setup::{Setup, CastlingSide, CastlingMode};
 pub use movelist::MoveList;
-pub use position::{IllegalMove, Outcome, Position, PositionError, Chess};
-pub use perft::perft;
+pub use position::{IllegalMove, IllegalMoveReason, Outcome, Position, PositionError, Chess};
+pub use chess960::Chess960;
+pub use packed::PackedMove;
+pub use perft::{perft, Perft, PerftStats};
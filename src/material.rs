@@ -0,0 +1,398 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Count material and read and write material signatures like `KQRvKR`.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use shakmaty::fen::Fen;
+//! use shakmaty::material::Material;
+//!
+//! let fen: Fen = "8/8/3k4/8/2r5/8/1QR5/4K3 w - - 0 1".parse()?;
+//! let material = Material::from_board(&fen.board);
+//! assert_eq!(material.to_string(), "KQRvKR");
+//!
+//! let mut material: Material = "KRvKQR".parse()?;
+//! assert!(material.normalize());
+//! assert_eq!(material.to_string(), "KQRvKR");
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+
+use std::fmt;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use std::error::Error;
+use std::borrow::Borrow;
+
+use types::{Color, White, Black, Role, Piece, Move};
+use board::Board;
+
+/// Roles in the order of a material signature.
+const SIGNATURE_ROLES: [Role; 6] = [Role::King, Role::Queen, Role::Rook, Role::Bishop, Role::Knight, Role::Pawn];
+
+/// Error when parsing an invalid material signature.
+pub struct InvalidMaterial {
+    _priv: (),
+}
+
+impl fmt::Debug for InvalidMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InvalidMaterial").finish()
+    }
+}
+
+impl fmt::Display for InvalidMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "invalid material".fmt(f)
+    }
+}
+
+impl Error for InvalidMaterial {
+    fn description(&self) -> &str {
+        "invalid material"
+    }
+}
+
+impl From<()> for InvalidMaterial {
+    fn from(_: ()) -> InvalidMaterial {
+        InvalidMaterial { _priv: () }
+    }
+}
+
+/// The material of one side.
+///
+/// Sides are ordered by strength: more pieces are stronger, then more
+/// queens, rooks, bishops, knights and pawns, in that order. This is the
+/// order of the sides in Syzygy tablebase names, e.g. `KPPvKR`.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct MaterialSide {
+    pub pawns: u8,
+    pub knights: u8,
+    pub bishops: u8,
+    pub rooks: u8,
+    pub queens: u8,
+    pub kings: u8,
+}
+
+impl MaterialSide {
+    pub fn by_role(&self, role: Role) -> u8 {
+        match role {
+            Role::Pawn => self.pawns,
+            Role::Knight => self.knights,
+            Role::Bishop => self.bishops,
+            Role::Rook => self.rooks,
+            Role::Queen => self.queens,
+            Role::King => self.kings,
+        }
+    }
+
+    pub fn by_role_mut(&mut self, role: Role) -> &mut u8 {
+        match role {
+            Role::Pawn => &mut self.pawns,
+            Role::Knight => &mut self.knights,
+            Role::Bishop => &mut self.bishops,
+            Role::Rook => &mut self.rooks,
+            Role::Queen => &mut self.queens,
+            Role::King => &mut self.kings,
+        }
+    }
+
+    pub fn count(&self) -> u8 {
+        self.pawns
+            .saturating_add(self.knights)
+            .saturating_add(self.bishops)
+            .saturating_add(self.rooks)
+            .saturating_add(self.queens)
+            .saturating_add(self.kings)
+    }
+}
+
+impl Ord for MaterialSide {
+    fn cmp(&self, other: &MaterialSide) -> Ordering {
+        self.count().cmp(&other.count()).then_with(|| {
+            SIGNATURE_ROLES.iter()
+                .map(|&role| self.by_role(role).cmp(&other.by_role(role)))
+                .find(|&ordering| ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        })
+    }
+}
+
+impl PartialOrd for MaterialSide {
+    fn partial_cmp(&self, other: &MaterialSide) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for MaterialSide {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &role in &SIGNATURE_ROLES {
+            let ch = role.char().to_ascii_uppercase().to_string();
+            f.write_str(&ch.repeat(self.by_role(role) as usize))?;
+        }
+        Ok(())
+    }
+}
+
+/// The material of both sides.
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Material {
+    pub white: MaterialSide,
+    pub black: MaterialSide,
+}
+
+impl Material {
+    /// No material at all.
+    pub fn empty() -> Material {
+        Material::default()
+    }
+
+    /// Counts the material on a board.
+    pub fn from_board(board: &Board) -> Material {
+        let mut material = Material::empty();
+        for &color in &[White, Black] {
+            for &role in &SIGNATURE_ROLES {
+                *material.by_piece_mut(role.of(color)) = board.by_piece(role.of(color)).count() as u8;
+            }
+        }
+        material
+    }
+
+    /// Parses a material signature like `KQRvKR`. The pieces of each side
+    /// may be given in any order and case.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidMaterial`] if the input is not a valid signature.
+    ///
+    /// [`InvalidMaterial`]: struct.InvalidMaterial.html
+    pub fn from_bytes(s: &[u8]) -> Result<Material, InvalidMaterial> {
+        let mut material = Material::empty();
+        let mut sides = s.split(|&ch| ch == b'v' || ch == b'V');
+
+        for &color in &[White, Black] {
+            for &ch in sides.next().ok_or(())? {
+                let role = Role::from_char(ch as char).ok_or(())?;
+                let count = material.by_piece_mut(role.of(color));
+                *count = count.checked_add(1).ok_or(())?;
+            }
+        }
+
+        if sides.next().is_some() {
+            return Err(InvalidMaterial { _priv: () });
+        }
+
+        Ok(material)
+    }
+
+    pub fn by_color(&self, color: Color) -> &MaterialSide {
+        color.fold(&self.white, &self.black)
+    }
+
+    pub fn by_color_mut(&mut self, color: Color) -> &mut MaterialSide {
+        color.fold(&mut self.white, &mut self.black)
+    }
+
+    pub fn by_piece(&self, piece: Piece) -> u8 {
+        self.by_color(piece.color).by_role(piece.role)
+    }
+
+    pub fn by_piece_mut(&mut self, piece: Piece) -> &mut u8 {
+        self.by_color_mut(piece.color).by_role_mut(piece.role)
+    }
+
+    pub fn count(&self) -> u8 {
+        self.white.count().saturating_add(self.black.count())
+    }
+
+    /// Tests if both sides have the same material.
+    pub fn is_symmetric(&self) -> bool {
+        self.white == self.black
+    }
+
+    /// Swap the material of both sides.
+    pub fn swap_colors(&mut self) {
+        ::std::mem::swap(&mut self.white, &mut self.black);
+    }
+
+    /// Put the stronger side first, as in the names of endgame tablebases.
+    /// Returns `true` if the colors have been swapped.
+    pub fn normalize(&mut self) -> bool {
+        if self.white < self.black {
+            self.swap_colors();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Update the material after `turn` plays `m`, accounting for captures,
+    /// promotions and drops.
+    pub fn play(&mut self, turn: Color, m: &Move) {
+        if let Some(capture) = m.capture() {
+            let count = self.by_piece_mut(capture.of(!turn));
+            *count = count.saturating_sub(1);
+        }
+
+        if let Some(promotion) = m.promotion() {
+            let pawns = self.by_piece_mut(turn.pawn());
+            *pawns = pawns.saturating_sub(1);
+            let count = self.by_piece_mut(promotion.of(turn));
+            *count = count.saturating_add(1);
+        }
+
+        if let Move::Put { role, .. } = *m {
+            let count = self.by_piece_mut(role.of(turn));
+            *count = count.saturating_add(1);
+        }
+    }
+}
+
+impl FromStr for Material {
+    type Err = InvalidMaterial;
+
+    fn from_str(s: &str) -> Result<Material, InvalidMaterial> {
+        Material::from_bytes(s.as_bytes())
+    }
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}v{}", self.white, self.black)
+    }
+}
+
+/// Iterator over the material changes in a sequence of moves, created by
+/// [`Board::material_changes()`].
+///
+/// Yields the index of each move that changes the material, together with
+/// the material after that move.
+///
+/// [`Board::material_changes()`]: ../struct.Board.html#method.material_changes
+#[derive(Debug)]
+pub struct MaterialChanges<I> {
+    material: Material,
+    turn: Color,
+    moves: I,
+    index: usize,
+}
+
+impl<I> MaterialChanges<I> {
+    /// Track material changes for `moves` played from `board`, starting with
+    /// `turn`. See [`Board::material_changes()`].
+    ///
+    /// [`Board::material_changes()`]: ../struct.Board.html#method.material_changes
+    pub fn new(board: &Board, turn: Color, moves: I) -> MaterialChanges<I> {
+        MaterialChanges {
+            material: Material::from_board(board),
+            turn,
+            moves,
+            index: 0,
+        }
+    }
+}
+
+impl<I> Iterator for MaterialChanges<I> where I: Iterator, I::Item: Borrow<Move> {
+    type Item = (usize, Material);
+
+    fn next(&mut self) -> Option<(usize, Material)> {
+        for m in &mut self.moves {
+            let m = m.borrow();
+            let index = self.index;
+            let turn = self.turn;
+            self.index += 1;
+            self.turn = !turn;
+
+            let changed = match *m {
+                Move::Put { .. } => true,
+                _ => m.capture().is_some() || m.promotion().is_some(),
+            };
+
+            if changed {
+                self.material.play(turn, m);
+                return Some((index, self.material.clone()));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use square::Square;
+    use fen::Fen;
+
+    #[test]
+    fn test_read_write() {
+        for signature in &["KvK", "KQRvKR", "KBNvK", "KPPPPPPPPvKPPPPPPPP", "vK", "v"] {
+            let material: Material = signature.parse().expect("valid signature");
+            assert_eq!(material.to_string(), *signature);
+        }
+
+        assert_eq!("kqvkp".parse::<Material>().expect("valid signature").to_string(), "KQvKP");
+        assert_eq!("KRQvK".parse::<Material>().expect("valid signature").to_string(), "KQRvK");
+
+        for signature in &["", "KQK", "KvKvK", "KXvK", "K vK"] {
+            assert!(signature.parse::<Material>().is_err(), "{} should be invalid", signature);
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        for &(signature, normalized, swapped) in &[
+            ("KvKQ", "KQvK", true),
+            ("KRvKQ", "KQvKR", true),
+            ("KPPvKR", "KPPvKR", false),
+            ("KRvKPP", "KPPvKR", true),
+            ("KBvKN", "KBvKN", false),
+            ("KNvKB", "KBvKN", true),
+            ("KRRvKQ", "KRRvKQ", false),
+            ("KQvKRR", "KRRvKQ", true),
+        ] {
+            let mut material: Material = signature.parse().expect("valid signature");
+            assert_eq!(material.normalize(), swapped);
+            assert_eq!(material.to_string(), normalized);
+        }
+    }
+
+    #[test]
+    fn test_material_changes() {
+        let fen: Fen = "4k3/1P6/8/8/8/8/3p4/4KB2 w - - 0 1".parse().expect("valid fen");
+        let moves = [
+            Move::Normal { role: Role::Pawn, from: Square::B7, capture: None, to: Square::B8, promotion: Some(Role::Queen) },
+            Move::Normal { role: Role::King, from: Square::E8, capture: None, to: Square::E7, promotion: None },
+            Move::Normal { role: Role::King, from: Square::E1, capture: Some(Role::Pawn), to: Square::D2, promotion: None },
+        ];
+
+        let changes: Vec<_> = fen.board.material_changes(White, &moves)
+            .map(|(index, material)| (index, material.to_string()))
+            .collect();
+
+        assert_eq!(changes, vec![(0, "KQBvKP".to_owned()), (2, "KQBvK".to_owned())]);
+    }
+}
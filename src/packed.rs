@@ -0,0 +1,706 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Compact binary encoding of positions and moves.
+//!
+//! Moves can be packed into 16 bits with [`PackedMove`], and whole games
+//! into a few bits per move with [`encode_game()`].
+//!
+//! A position is stored as:
+//!
+//! * the occupied squares as a little endian `u64`,
+//! * a 4-bit piece code for each occupied square (low nibble first),
+//! * a flags byte (side to move and which optional fields follow),
+//! * the en passant square, castling rights (one byte of rook files per
+//!   side), promoted pieces, pockets and remaining checks, if present,
+//! * halfmove clock and fullmove number as LEB128 varints.
+//!
+//! The starting position takes 29 bytes.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use shakmaty::Chess;
+//! use shakmaty::fen::Fen;
+//! use shakmaty::packed;
+//!
+//! let pos = Chess::default();
+//! let bytes = packed::encode(&pos);
+//! assert_eq!(bytes.len(), 29);
+//!
+//! let fen = packed::decode(&bytes)?;
+//! assert_eq!(fen, Fen::default());
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`PackedMove`]: struct.PackedMove.html
+//! [`encode_game()`]: fn.encode_game.html
+
+use std::fmt;
+use std::error::Error;
+
+use square::Square;
+use types::{Color, Piece, Move, Pockets, RemainingChecks, ROLES};
+use bitboard::Bitboard;
+use board::Board;
+use setup::Setup;
+use fen::Fen;
+use position::{Position, IllegalMove, IllegalMoveReason};
+use movelist::MoveList;
+
+const BLACK_TO_MOVE: u8 = 1;
+const HAS_EP_SQUARE: u8 = 2;
+const HAS_CASTLING_RIGHTS: u8 = 4;
+const HAS_PROMOTED: u8 = 8;
+const HAS_POCKETS: u8 = 16;
+const HAS_REMAINING_CHECKS: u8 = 32;
+
+/// Error when decoding an invalid binary position.
+pub struct InvalidPacked {
+    _priv: (),
+}
+
+impl fmt::Debug for InvalidPacked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("InvalidPacked").finish()
+    }
+}
+
+impl fmt::Display for InvalidPacked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        "invalid packed position".fmt(f)
+    }
+}
+
+impl Error for InvalidPacked {
+    fn description(&self) -> &str {
+        "invalid packed position"
+    }
+}
+
+impl From<()> for InvalidPacked {
+    fn from(_: ()) -> InvalidPacked {
+        InvalidPacked { _priv: () }
+    }
+}
+
+fn piece_code(piece: Piece) -> u8 {
+    piece.role as u8 | piece.color.fold(0, 8)
+}
+
+fn piece_from_code(code: u8) -> Option<Piece> {
+    ROLES.get((code & 7) as usize).map(|role| role.of(Color::from_bool(code & 8 == 0)))
+}
+
+fn write_u64(buf: &mut Vec<u8>, v: u64) {
+    for i in 0..8 {
+        buf.push((v >> (8 * i)) as u8);
+    }
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut v: u32) {
+    while v >= 0x80 {
+        buf.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+/// Encodes the setup.
+///
+/// Castling rights are stored as files of rooks on the backranks. Any other
+/// castling rights of the setup are not preserved.
+pub fn encode(setup: &Setup) -> Vec<u8> {
+    let board = setup.board();
+    let mut buf = Vec::with_capacity(32);
+
+    write_u64(&mut buf, board.occupied().0);
+
+    let mut nibbles = board.occupied().map(|sq| {
+        board.piece_at(sq).map_or(0, piece_code)
+    });
+    while let Some(lo) = nibbles.next() {
+        buf.push(lo | nibbles.next().unwrap_or(0) << 4);
+    }
+
+    let castling_rights = setup.castling_rights() & Bitboard::BACKRANKS;
+
+    let mut flags = setup.turn().fold(0, BLACK_TO_MOVE);
+    if setup.ep_square().is_some() { flags |= HAS_EP_SQUARE; }
+    if castling_rights.any() { flags |= HAS_CASTLING_RIGHTS; }
+    if board.promoted().any() { flags |= HAS_PROMOTED; }
+    if setup.pockets().is_some() { flags |= HAS_POCKETS; }
+    if setup.remaining_checks().is_some() { flags |= HAS_REMAINING_CHECKS; }
+    buf.push(flags);
+
+    if let Some(ep_square) = setup.ep_square() {
+        buf.push(u8::from(ep_square));
+    }
+
+    if castling_rights.any() {
+        buf.push(castling_rights.0 as u8);
+        buf.push((castling_rights.0 >> 56) as u8);
+    }
+
+    if board.promoted().any() {
+        write_u64(&mut buf, board.promoted().0);
+    }
+
+    if let Some(pockets) = setup.pockets() {
+        for &color in &[Color::White, Color::Black] {
+            for &role in &ROLES {
+                buf.push(pockets.by_color(color).by_role(role));
+            }
+        }
+    }
+
+    if let Some(remaining_checks) = setup.remaining_checks() {
+        buf.push(remaining_checks.white);
+        buf.push(remaining_checks.black);
+    }
+
+    write_varint(&mut buf, setup.halfmove_clock());
+    write_varint(&mut buf, setup.fullmoves());
+
+    buf
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn u8(&mut self) -> Result<u8, InvalidPacked> {
+        let (&head, tail) = self.bytes.split_first().ok_or(())?;
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> Result<u64, InvalidPacked> {
+        let mut v = 0;
+        for i in 0..8 {
+            v |= u64::from(self.u8()?) << (8 * i);
+        }
+        Ok(v)
+    }
+
+    fn varint(&mut self) -> Result<u32, InvalidPacked> {
+        let mut v: u32 = 0;
+        for shift in (0..5).map(|i| i * 7) {
+            let byte = self.u8()?;
+            let payload = u32::from(byte & 0x7f);
+            if (payload << shift) >> shift != payload {
+                // The 5th byte has bits that do not fit into a u32.
+                return Err(InvalidPacked { _priv: () });
+            }
+            v |= payload << shift;
+            if byte & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(InvalidPacked { _priv: () })
+    }
+
+    fn square(&mut self) -> Result<Square, InvalidPacked> {
+        Square::from_index(self.u8()? as i8).ok_or(()).map_err(InvalidPacked::from)
+    }
+}
+
+/// Decodes a position that was encoded with [`encode()`].
+///
+/// # Errors
+///
+/// Returns [`InvalidPacked`] if the input is truncated, has trailing bytes
+/// or contains invalid piece codes or squares.
+///
+/// [`encode()`]: fn.encode.html
+/// [`InvalidPacked`]: struct.InvalidPacked.html
+pub fn decode(bytes: &[u8]) -> Result<Fen, InvalidPacked> {
+    let mut reader = Reader { bytes };
+
+    let occupied = Bitboard(reader.u64()?);
+    let mut board = Board::empty();
+    let mut byte = 0;
+    for (i, sq) in occupied.enumerate() {
+        let code = if i % 2 == 0 {
+            byte = reader.u8()?;
+            byte & 15
+        } else {
+            byte >> 4
+        };
+        board.set_piece_at(sq, piece_from_code(code).ok_or(())?, false);
+    }
+
+    let flags = reader.u8()?;
+    if flags & !(BLACK_TO_MOVE | HAS_EP_SQUARE | HAS_CASTLING_RIGHTS |
+                 HAS_PROMOTED | HAS_POCKETS | HAS_REMAINING_CHECKS) != 0 {
+        return Err(InvalidPacked { _priv: () });
+    }
+
+    let mut fen = Fen::empty();
+    fen.turn = Color::from_bool(flags & BLACK_TO_MOVE == 0);
+
+    if flags & HAS_EP_SQUARE != 0 {
+        fen.ep_square = Some(reader.square()?);
+    }
+
+    if flags & HAS_CASTLING_RIGHTS != 0 {
+        let white = u64::from(reader.u8()?);
+        let black = u64::from(reader.u8()?);
+        fen.castling_rights = Bitboard(white | black << 56);
+    }
+
+    if flags & HAS_PROMOTED != 0 {
+        let promoted = Bitboard(reader.u64()?);
+        if !(promoted & !occupied).is_empty() {
+            return Err(InvalidPacked { _priv: () });
+        }
+        for sq in promoted {
+            let piece = board.piece_at(sq).ok_or(())?;
+            board.set_piece_at(sq, piece, true);
+        }
+    }
+
+    if flags & HAS_POCKETS != 0 {
+        let mut pockets = Pockets::default();
+        for &color in &[Color::White, Color::Black] {
+            for &role in &ROLES {
+                *pockets.by_color_mut(color).by_role_mut(role) = reader.u8()?;
+            }
+        }
+        fen.pockets = Some(pockets);
+    }
+
+    if flags & HAS_REMAINING_CHECKS != 0 {
+        fen.remaining_checks = Some(RemainingChecks {
+            white: reader.u8()?,
+            black: reader.u8()?,
+        });
+    }
+
+    fen.halfmove_clock = reader.varint()?;
+    fen.fullmoves = reader.varint()?;
+    fen.board = board;
+
+    if reader.bytes.is_empty() {
+        Ok(fen)
+    } else {
+        Err(InvalidPacked { _priv: () })
+    }
+}
+
+const CASTLE: u16 = 6;
+const EN_PASSANT: u16 = 7;
+const PUT: u16 = 8;
+
+/// A [`Move`] packed into 16 bits.
+///
+/// Bits 0-5 hold the target square and bits 6-11 the origin square (or the
+/// role of a dropped piece). Bits 12-15 are `0` for normal moves, the role
+/// of the promoted piece for promotions, `6` for castling (from the king to
+/// the rook), `7` for en passant and `8` for drops.
+///
+/// Role and capture information is not stored and restored from the
+/// position in [`to_move()`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use shakmaty::{Chess, Move, Role, Square};
+///
+/// let pos = Chess::default();
+/// let m = Move::Normal {
+///     role: Role::Knight,
+///     from: Square::G1,
+///     capture: None,
+///     to: Square::F3,
+///     promotion: None,
+/// };
+///
+/// let packed = m.to_packed();
+/// assert_eq!(packed.to_move(&pos)?, m);
+/// #
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`Move`]: ../enum.Move.html
+/// [`to_move()`]: #method.to_move
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PackedMove(pub u16);
+
+impl PackedMove {
+    fn new(from: u16, to: Square, kind: u16) -> PackedMove {
+        PackedMove(u16::from(u8::from(to)) | from << 6 | kind << 12)
+    }
+
+    fn kind(self) -> u16 {
+        self.0 >> 12
+    }
+
+    fn origin_bits(self) -> u16 {
+        (self.0 >> 6) & 63
+    }
+
+    /// Gets the origin square or `None` for drops.
+    pub fn from(self) -> Option<Square> {
+        if self.kind() == PUT {
+            None
+        } else {
+            Some(Square::new(self.origin_bits() as i8))
+        }
+    }
+
+    /// Gets the target square. For castling moves this is the corresponding
+    /// rook square.
+    pub fn to(self) -> Square {
+        Square::new((self.0 & 63) as i8)
+    }
+
+    /// Restores the full [`Move`] in the context of a position.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IllegalMove`] if the move is not legal or the packed
+    /// representation is invalid.
+    ///
+    /// [`Move`]: ../enum.Move.html
+    /// [`IllegalMove`]: ../struct.IllegalMove.html
+    pub fn to_move<P: Position>(self, pos: &P) -> Result<Move, IllegalMove> {
+        let to = self.to();
+
+        let candidate = match self.kind() {
+            PUT => Move::Put {
+                role: *ROLES.get(self.origin_bits() as usize)
+                    .ok_or(IllegalMove { reason: IllegalMoveReason::Other })?,
+                to,
+            },
+            CASTLE => Move::Castle { king: Square::new(self.origin_bits() as i8), rook: to },
+            EN_PASSANT => Move::EnPassant { from: Square::new(self.origin_bits() as i8), to },
+            kind if kind < CASTLE => {
+                let from = Square::new(self.origin_bits() as i8);
+                Move::Normal {
+                    role: pos.board().role_at(from)
+                        .ok_or(IllegalMove { reason: IllegalMoveReason::NoPiece })?,
+                    from,
+                    capture: pos.board().role_at(to),
+                    to,
+                    promotion: if kind == 0 { None } else { Some(ROLES[kind as usize]) },
+                }
+            },
+            _ => return Err(IllegalMove { reason: IllegalMoveReason::Other }),
+        };
+
+        if pos.is_legal(&candidate) {
+            Ok(candidate)
+        } else {
+            Err(IllegalMove::explain(pos, &candidate))
+        }
+    }
+}
+
+impl<'a> From<&'a Move> for PackedMove {
+    fn from(m: &'a Move) -> PackedMove {
+        match *m {
+            Move::Normal { from, to, promotion, .. } =>
+                PackedMove::new(u16::from(u8::from(from)), to, promotion.map_or(0, |r| r as u16)),
+            Move::EnPassant { from, to } =>
+                PackedMove::new(u16::from(u8::from(from)), to, EN_PASSANT),
+            Move::Castle { king, rook } =>
+                PackedMove::new(u16::from(u8::from(king)), rook, CASTLE),
+            Move::Put { role, to } =>
+                PackedMove::new(role as u16, to, PUT),
+        }
+    }
+}
+
+impl Move {
+    /// Packs the move into 16 bits. See [`PackedMove`].
+    ///
+    /// [`PackedMove`]: packed/struct.PackedMove.html
+    pub fn to_packed(&self) -> PackedMove {
+        self.into()
+    }
+}
+
+/// Legal moves in canonical order, sorted by their packed representation.
+fn canonical_legals<P: Position>(pos: &P) -> MoveList {
+    let mut legals = pos.legals();
+    legals.sort_by_key(|m| m.to_packed().0);
+    legals
+}
+
+fn index_bits(legals: usize) -> u32 {
+    if legals <= 1 {
+        0
+    } else {
+        32 - (legals as u32 - 1).leading_zeros()
+    }
+}
+
+/// Encodes the moves of a game, starting from `pos`.
+///
+/// Each move is stored as its index in the list of legal moves, sorted by
+/// [`PackedMove`], using just enough bits to distinguish all legal moves.
+/// Forced moves take no space at all. The result is prefixed with the number
+/// of moves as a LEB128 varint.
+///
+/// The starting position is not included. Use [`encode()`] to store it
+/// separately, if needed.
+///
+/// # Errors
+///
+/// Returns [`IllegalMove`] if any move is not legal.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use shakmaty::Chess;
+/// use shakmaty::san::San;
+/// use shakmaty::packed;
+/// # use shakmaty::Position;
+///
+/// let mut pos = Chess::default();
+/// let mut moves = Vec::new();
+/// for san in &["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"] {
+///     let m = san.parse::<San>()?.to_move(&pos)?;
+///     pos.play_unchecked(&m);
+///     moves.push(m);
+/// }
+///
+/// let bytes = packed::encode_game(&Chess::default(), &moves)?;
+/// assert_eq!(bytes.len(), 6);
+/// assert_eq!(packed::decode_game(&Chess::default(), &bytes)?, moves);
+/// #
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`PackedMove`]: struct.PackedMove.html
+/// [`encode()`]: fn.encode.html
+/// [`IllegalMove`]: ../struct.IllegalMove.html
+pub fn encode_game<P: Position + Clone>(pos: &P, moves: &[Move]) -> Result<Vec<u8>, IllegalMove> {
+    let mut buf = Vec::new();
+    write_varint(&mut buf, moves.len() as u32);
+
+    let mut pos = pos.clone();
+    let mut acc = 0u64;
+    let mut acc_bits = 0;
+
+    for m in moves {
+        let legals = canonical_legals(&pos);
+        let index = legals.iter().position(|l| l == m).ok_or_else(|| IllegalMove::explain(&pos, m))?;
+
+        acc |= (index as u64) << acc_bits;
+        acc_bits += index_bits(legals.len());
+        while acc_bits >= 8 {
+            buf.push(acc as u8);
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+
+        pos.play_unchecked(m);
+    }
+
+    if acc_bits > 0 {
+        buf.push(acc as u8);
+    }
+
+    Ok(buf)
+}
+
+/// Decodes the moves of a game that was encoded with [`encode_game()`],
+/// replaying them from `pos`.
+///
+/// # Errors
+///
+/// Returns [`InvalidPacked`] if the input is truncated, has trailing bytes
+/// or a move index is out of range.
+///
+/// [`encode_game()`]: fn.encode_game.html
+/// [`InvalidPacked`]: struct.InvalidPacked.html
+pub fn decode_game<P: Position + Clone>(pos: &P, bytes: &[u8]) -> Result<Vec<Move>, InvalidPacked> {
+    let mut reader = Reader { bytes };
+    let num_moves = reader.varint()? as usize;
+
+    let mut pos = pos.clone();
+    let mut moves = Vec::with_capacity(num_moves.min(1024));
+    let mut acc = 0u64;
+    let mut acc_bits = 0;
+
+    for _ in 0..num_moves {
+        let mut legals = canonical_legals(&pos);
+        let bits = index_bits(legals.len());
+        while acc_bits < bits {
+            acc |= u64::from(reader.u8()?) << acc_bits;
+            acc_bits += 8;
+        }
+
+        let index = (acc & ((1 << bits) - 1)) as usize;
+        acc >>= bits;
+        acc_bits -= bits;
+
+        if index >= legals.len() {
+            return Err(InvalidPacked { _priv: () });
+        }
+
+        let m = legals.swap_remove(index);
+        pos.play_unchecked(&m);
+        moves.push(m);
+    }
+
+    if reader.bytes.is_empty() {
+        Ok(moves)
+    } else {
+        Err(InvalidPacked { _priv: () })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(fen: &str) -> usize {
+        let fen: Fen = fen.parse().expect("valid fen");
+        let bytes = encode(&fen);
+        assert_eq!(decode(&bytes).expect("decodes"), fen);
+        bytes.len()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        assert_eq!(roundtrip("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), 29);
+        assert!(roundtrip("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1") <= 32);
+        roundtrip("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3");
+        roundtrip("8/8/8/8/8/8/8/8 b - - 150 1000");
+        roundtrip("rnbqkbr1/p1pppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b HAa - 0 1");
+        roundtrip("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R[Nq] w KQkq - 0 3");
+        roundtrip("rnbqk~Q1r/ppppp2p/8/8/8/8/PPPPPPPP/RNBQKBNR[] b KQq - 0 5");
+        roundtrip("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 3+2 0 1");
+    }
+
+    #[test]
+    fn test_invalid() {
+        let bytes = encode(&Fen::default());
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(decode(&trailing).is_err());
+
+        let mut bad_piece = bytes.clone();
+        bad_piece[8] = 0x07;
+        assert!(decode(&bad_piece).is_err());
+
+        assert!(decode(&[]).is_err());
+    }
+
+    #[test]
+    fn test_varint() {
+        let mut bytes = encode(&Fen::default());
+        bytes.pop();
+
+        let mut max = bytes.clone();
+        max.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0x0f]);
+        assert_eq!(decode(&max).expect("u32::MAX fullmoves").fullmoves, ::std::u32::MAX);
+
+        let mut overflow = bytes.clone();
+        overflow.extend_from_slice(&[0x81, 0x80, 0x80, 0x80, 0x10]);
+        assert!(decode(&overflow).is_err());
+
+        let mut overlong = bytes.clone();
+        overlong.extend_from_slice(&[0x81, 0x80, 0x80, 0x80, 0x80, 0x00]);
+        assert!(decode(&overlong).is_err());
+    }
+
+    #[test]
+    fn test_packed_move() {
+        use position::Chess;
+
+        for fen in &["r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+                     "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+                     "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1"] {
+            let fen: Fen = fen.parse().expect("valid fen");
+            let pos: Chess = fen.position().expect("legal position");
+            for m in pos.legals() {
+                assert_eq!(m.to_packed().to_move(&pos).expect("legal"), m);
+            }
+        }
+
+        let pos = Chess::default();
+        assert!(PackedMove(0).to_move(&pos).is_err());
+        assert!(PackedMove(0xf000).to_move(&pos).is_err());
+    }
+
+    #[test]
+    fn test_game() {
+        use position::Chess;
+
+        let pos = Chess::default();
+        let mut moves = Vec::new();
+        let mut current = pos.clone();
+        for i in 0..60 {
+            let legals = canonical_legals(&current);
+            if legals.is_empty() {
+                break;
+            }
+            let m = legals[(i * 7) % legals.len()].clone();
+            current.play_unchecked(&m);
+            moves.push(m);
+        }
+
+        let bytes = encode_game(&pos, &moves).expect("legal moves");
+        assert!(bytes.len() < moves.len());
+        assert_eq!(decode_game(&pos, &bytes).expect("valid game"), moves);
+
+        assert_eq!(encode_game(&pos, &[]).expect("empty game"), vec![0]);
+        assert!(decode_game(&pos, &bytes[..bytes.len() - 1]).is_err());
+        assert!(encode_game(&pos, &[moves[1].clone()]).is_err());
+    }
+
+    #[test]
+    fn test_packed_put() {
+        use types::Role;
+
+        let m = Move::Put { role: Role::Knight, to: Square::F3 };
+        let packed = m.to_packed();
+        assert_eq!(packed.from(), None);
+        assert_eq!(packed.to(), Square::F3);
+    }
+}
@@ -0,0 +1,208 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Pawn structure queries.
+//!
+//! Spans and attacks operate on arbitrary sets of pawns of the given color.
+//! The structure queries look at the pawns on a [`Board`] and return the
+//! matching pawns (or squares, or files) of the given color.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! #
+//! # fn try_main() -> Result<(), Box<Error>> {
+//! use shakmaty::{Board, Bitboard, Square};
+//! use shakmaty::Color::White;
+//! use shakmaty::pawns;
+//!
+//! let board: Board = "4k3/5p2/8/1pP5/8/4P3/4P3/4K3".parse()?;
+//! assert_eq!(pawns::passed(&board, White), Bitboard::from_square(Square::C5));
+//! assert_eq!(pawns::doubled(&board, White), Bitboard::from_square(Square::E2).with(Square::E3));
+//! assert_eq!(pawns::islands(&board, White), 2);
+//! #
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+//!
+//! [`Board`]: ../struct.Board.html
+
+use types::Color;
+use bitboard::Bitboard;
+use board::Board;
+
+#[inline]
+fn west(bb: Bitboard) -> Bitboard {
+    Bitboard((bb & !Bitboard::file(0)).0 >> 1)
+}
+
+#[inline]
+fn east(bb: Bitboard) -> Bitboard {
+    Bitboard((bb & !Bitboard::file(7)).0 << 1)
+}
+
+#[inline]
+fn fill_forward(color: Color, mut bb: Bitboard) -> Bitboard {
+    bb |= bb.relative_shift(color, 8);
+    bb |= bb.relative_shift(color, 16);
+    bb | bb.relative_shift(color, 32)
+}
+
+#[inline]
+fn file_fill(bb: Bitboard) -> Bitboard {
+    fill_forward(Color::White, bb) | fill_forward(Color::Black, bb)
+}
+
+/// Squares attacked by `pawns` of the given color.
+pub fn attacks(color: Color, pawns: Bitboard) -> Bitboard {
+    let pushed = pawns.relative_shift(color, 8);
+    west(pushed) | east(pushed)
+}
+
+/// Squares in front of `pawns` on the same file, from the point of view of
+/// `color`.
+pub fn front_span(color: Color, pawns: Bitboard) -> Bitboard {
+    fill_forward(color, pawns.relative_shift(color, 8))
+}
+
+/// Squares behind `pawns` on the same file, from the point of view of
+/// `color`.
+pub fn rear_span(color: Color, pawns: Bitboard) -> Bitboard {
+    front_span(!color, pawns)
+}
+
+/// Squares that `pawns` of the given color could attack while advancing.
+pub fn attack_span(color: Color, pawns: Bitboard) -> Bitboard {
+    let span = front_span(color, pawns);
+    west(span) | east(span)
+}
+
+/// Pawns that have no opposing pawns in front of them, neither on the same
+/// nor on adjacent files.
+pub fn passed(board: &Board, color: Color) -> Bitboard {
+    let ours = board.pawns() & board.by_color(color);
+    let theirs = board.pawns() & board.by_color(!color);
+    ours & !front_span(!color, theirs) & !attack_span(!color, theirs)
+}
+
+/// Pawns without friendly pawns on adjacent files.
+pub fn isolated(board: &Board, color: Color) -> Bitboard {
+    let ours = board.pawns() & board.by_color(color);
+    ours & !file_fill(west(ours) | east(ours))
+}
+
+/// Pawns that share their file with another friendly pawn.
+pub fn doubled(board: &Board, color: Color) -> Bitboard {
+    let ours = board.pawns() & board.by_color(color);
+    ours & (front_span(color, ours) | rear_span(color, ours))
+}
+
+/// Pawns that can not be defended by friendly pawns advancing, and whose
+/// stop square is controlled by an opposing pawn.
+pub fn backward(board: &Board, color: Color) -> Bitboard {
+    let ours = board.pawns() & board.by_color(color);
+    let theirs = board.pawns() & board.by_color(!color);
+    let stops = ours.relative_shift(color, 8) & !attack_span(color, ours) & attacks(!color, theirs);
+    stops.relative_shift(!color, 8)
+}
+
+/// Pawns that are defended by a friendly pawn or have a friendly pawn next
+/// to them on the same rank.
+pub fn connected(board: &Board, color: Color) -> Bitboard {
+    let ours = board.pawns() & board.by_color(color);
+    ours & (attacks(color, ours) | west(ours) | east(ours))
+}
+
+/// The number of groups of friendly pawns on adjacent files.
+pub fn islands(board: &Board, color: Color) -> u32 {
+    let files = (file_fill(board.pawns() & board.by_color(color)) & Bitboard::rank(0)).0;
+    (files & !(files << 1)).count_ones()
+}
+
+/// Files without any pawns.
+pub fn open_files(board: &Board) -> Bitboard {
+    !file_fill(board.pawns())
+}
+
+/// Files without friendly pawns, but with opposing pawns.
+pub fn half_open_files(board: &Board, color: Color) -> Bitboard {
+    let ours = board.pawns() & board.by_color(color);
+    let theirs = board.pawns() & board.by_color(!color);
+    !file_fill(ours) & file_fill(theirs)
+}
+
+/// Squares on the 4th to 6th rank (from the point of view of `color`) that
+/// are defended by a friendly pawn and can never be attacked by opposing
+/// pawns.
+pub fn outposts(board: &Board, color: Color) -> Bitboard {
+    let ours = board.pawns() & board.by_color(color);
+    let theirs = board.pawns() & board.by_color(!color);
+    let ranks = Bitboard::relative_rank(color, 3) |
+                Bitboard::relative_rank(color, 4) |
+                Bitboard::relative_rank(color, 5);
+    ranks & attacks(color, ours) & !attack_span(!color, theirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use square::Square;
+    use types::{White, Black};
+
+    fn squares(sqs: &[Square]) -> Bitboard {
+        sqs.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_spans() {
+        let pawns = squares(&[Square::B2, Square::G6]);
+        assert_eq!(front_span(White, pawns), squares(&[
+            Square::B3, Square::B4, Square::B5, Square::B6, Square::B7, Square::B8,
+            Square::G7, Square::G8]));
+        assert_eq!(rear_span(White, pawns), squares(&[
+            Square::B1,
+            Square::G5, Square::G4, Square::G3, Square::G2, Square::G1]));
+        assert_eq!(attack_span(Black, Bitboard::from_square(Square::A3)),
+                   squares(&[Square::B2, Square::B1]));
+        assert_eq!(attacks(White, pawns),
+                   squares(&[Square::A3, Square::C3, Square::F7, Square::H7]));
+    }
+
+    #[test]
+    fn test_structure() {
+        let board: Board = "4k3/p4p2/1p2p3/3pP1P1/1P1P4/2P5/P3P3/4K3".parse().expect("valid board");
+
+        assert_eq!(passed(&board, White), Bitboard(0));
+        assert_eq!(isolated(&board, White), squares(&[Square::G5]));
+        assert_eq!(isolated(&board, Black), Bitboard(0));
+        assert_eq!(doubled(&board, White), squares(&[Square::E2, Square::E5]));
+        assert_eq!(backward(&board, White), squares(&[Square::C3, Square::G5]));
+        assert_eq!(backward(&board, Black), squares(&[Square::F7]));
+        assert_eq!(connected(&board, White), squares(&[Square::B4, Square::D4, Square::E5]));
+        assert_eq!(islands(&board, White), 2);
+        assert_eq!(islands(&board, Black), 2);
+        assert_eq!(open_files(&board), Bitboard::file(7));
+        assert_eq!(half_open_files(&board, White), Bitboard::file(5));
+        assert_eq!(half_open_files(&board, Black), Bitboard::file(2) | Bitboard::file(6));
+        assert_eq!(outposts(&board, White), squares(&[Square::D6, Square::F6, Square::H6]));
+        assert_eq!(outposts(&board, Black), squares(&[Square::C4, Square::E4]));
+    }
+}
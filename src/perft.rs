@@ -14,9 +14,30 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
-use position::Position;
+//! Count legal move paths to test move generation.
+//!
+//! See [`perft()`], [`Perft`] and perft test suites in [`Suite`].
+//!
+//! [`perft()`]: fn.perft.html
+//! [`Perft`]: struct.Perft.html
+//! [`Suite`]: struct.Suite.html
+
+use std::cmp::max;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::str;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, AtomicU64, Ordering};
+use std::thread;
+
+use fen::Fen;
+use position::{Position, PositionError};
 use movelist::MoveList;
-use uci::Uci;
+use square::Square;
+use types::Move;
+use zobrist;
 
 /// Counts legal move paths of a given length.
 ///
@@ -24,6 +45,9 @@ use uci::Uci;
 /// position. Useful for comparing, testing and debugging move generation
 /// correctness and performance.
 ///
+/// See [`Perft`] for a configurable version with multiple threads, a hash
+/// table and per-move results.
+///
 /// # Examples
 ///
 /// ```
@@ -35,6 +59,8 @@ use uci::Uci;
 /// assert_eq!(perft(&pos, 2), 400);
 /// assert_eq!(perft(&pos, 3), 8902);
 /// ```
+///
+/// [`Perft`]: struct.Perft.html
 pub fn perft<P: Position + Clone>(pos: &P, depth: u8) -> usize {
     if depth < 1 {
         1
@@ -54,22 +80,562 @@ pub fn perft<P: Position + Clone>(pos: &P, depth: u8) -> usize {
     }
 }
 
-/// Like `perft()`, but also prints the perft of each child for debugging.
-#[allow(unused)]
-pub fn debug_perft<P: Position + Clone>(pos: &P, depth: u8) -> usize {
-    if depth < 1 {
-        1
-    } else {
+/// Perft options.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Chess, Perft};
+///
+/// let pos = Chess::default();
+///
+/// let mut opts = Perft::new();
+/// opts.threads(4).hash_size(1 << 20);
+///
+/// assert_eq!(opts.count(&pos, 4), 197281);
+///
+/// let divided = opts.divide(&pos, 2);
+/// assert_eq!(divided.len(), 20);
+/// assert!(divided.iter().all(|&(_, nodes)| nodes == 20));
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Perft {
+    threads: usize,
+    hash_size: usize,
+}
+
+impl Perft {
+    /// Single threaded perft without a hash table.
+    pub fn new() -> Perft {
+        Perft {
+            threads: 1,
+            hash_size: 0,
+        }
+    }
+
+    /// Set the number of worker threads. The moves of the root position are
+    /// distributed among the threads.
+    pub fn threads(&mut self, threads: usize) -> &mut Perft {
+        self.threads = max(threads, 1);
+        self
+    }
+
+    /// Set the size of the hash table in bytes, or `0` to disable it.
+    ///
+    /// Subtree node counts are cached by position hash and remaining depth.
+    /// The table is shared by all threads.
+    pub fn hash_size(&mut self, hash_size: usize) -> &mut Perft {
+        self.hash_size = hash_size;
+        self
+    }
+
+    /// Counts legal move paths of a given length. Like [`perft()`].
+    ///
+    /// [`perft()`]: fn.perft.html
+    pub fn count<P>(&self, pos: &P, depth: u8) -> u64
+        where P: Position + Clone + Send + Sync + 'static
+    {
+        if depth < 2 {
+            perft(pos, depth) as u64
+        } else {
+            self.divide(pos, depth).iter().map(|&(_, nodes)| nodes).sum()
+        }
+    }
+
+    /// Counts legal move paths of a given length for each legal move of the
+    /// root position, in move generation order.
+    ///
+    /// A perft of depth `0` has no root moves, so the result is empty.
+    pub fn divide<P>(&self, pos: &P, depth: u8) -> Vec<(Move, u64)>
+        where P: Position + Clone + Send + Sync + 'static
+    {
+        if depth < 1 {
+            return Vec::new();
+        }
+
+        let mut moves = MoveList::new();
+        pos.legal_moves(&mut moves);
+
+        let table = Arc::new(Table::with_size(self.hash_size));
+        let nodes = self.map_children(pos, &moves, move |child| {
+            perft_inner(child, depth - 1, &table)
+        });
+
+        moves.drain(..).zip(nodes).collect()
+    }
+
+    /// Counts legal move paths of a given length, broken down by the type of
+    /// the final move. The returned vector contains the statistics for each
+    /// depth from `1` to `depth`.
+    ///
+    /// Does not use the hash table.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Perft};
+    ///
+    /// let pos = Chess::default();
+    /// let stats = Perft::new().stats(&pos, 4);
+    ///
+    /// assert_eq!(stats[3].nodes, 197281);
+    /// assert_eq!(stats[3].captures, 1576);
+    /// assert_eq!(stats[3].checks, 469);
+    /// assert_eq!(stats[3].checkmates, 8);
+    /// ```
+    pub fn stats<P>(&self, pos: &P, depth: u8) -> Vec<PerftStats>
+        where P: Position + Clone + Send + Sync + 'static
+    {
+        let mut stats = vec![PerftStats::default(); depth as usize];
+
+        if depth < 1 {
+            return stats;
+        }
+
+        let mut moves = MoveList::new();
+        pos.legal_moves(&mut moves);
+
+        for m in &moves {
+            let mut child = pos.clone();
+            child.play_unchecked(m);
+            stats[0].record(&child, m);
+        }
+
+        for child_stats in self.map_children(pos, &moves, move |child| {
+            let mut child_stats = vec![PerftStats::default(); depth as usize - 1];
+            stats_inner(child, &mut child_stats);
+            child_stats
+        }) {
+            for (total, s) in stats[1..].iter_mut().zip(child_stats) {
+                total.add(&s);
+            }
+        }
+
+        stats
+    }
+
+    /// Applies `f` to the children of `pos`, distributing the work among the
+    /// configured number of threads. Results are in the order of `moves`.
+    fn map_children<P, T, F>(&self, pos: &P, moves: &MoveList, f: F) -> Vec<T>
+        where P: Position + Clone + Send + Sync + 'static,
+              T: Send + 'static,
+              F: Fn(&P) -> T + Send + Sync + 'static
+    {
+        let children: Vec<P> = moves.iter().map(|m| {
+            let mut child = pos.clone();
+            child.play_unchecked(m);
+            child
+        }).collect();
+
+        if self.threads < 2 || children.len() < 2 {
+            return children.iter().map(f).collect();
+        }
+
+        let children = Arc::new(children);
+        let f = Arc::new(f);
+        let next = Arc::new(AtomicUsize::new(0));
+
+        let workers: Vec<_> = (0..self.threads).map(|_| {
+            let children = Arc::clone(&children);
+            let f = Arc::clone(&f);
+            let next = Arc::clone(&next);
+
+            thread::spawn(move || {
+                let mut results = Vec::new();
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    match children.get(idx) {
+                        Some(child) => results.push((idx, f(child))),
+                        None => return results,
+                    }
+                }
+            })
+        }).collect();
+
+        let mut results: Vec<Option<T>> = children.iter().map(|_| None).collect();
+        for worker in workers {
+            for (idx, result) in worker.join().expect("perft worker panicked") {
+                results[idx] = Some(result);
+            }
+        }
+        results.into_iter().map(|r| r.expect("all children visited")).collect()
+    }
+}
+
+/// Perft node counts at a single depth, broken down by the type of the final
+/// move.
+///
+/// Discovered checks include double checks by discovery, matching the tables
+/// on the [Chess Programming Wiki](https://chessprogramming.wikispaces.com/Perft+Results).
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct PerftStats {
+    pub nodes: u64,
+    pub captures: u64,
+    pub en_passant: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    pub checks: u64,
+    pub discovered_checks: u64,
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
+
+impl PerftStats {
+    /// Record the move `m` leading to the position `child`.
+    fn record<P: Position>(&mut self, child: &P, m: &Move) {
+        self.nodes += 1;
+
+        if m.capture().is_some() {
+            self.captures += 1;
+        }
+
+        match *m {
+            Move::EnPassant { .. } => self.en_passant += 1,
+            Move::Castle { .. } => self.castles += 1,
+            Move::Normal { promotion: Some(_), .. } => self.promotions += 1,
+            _ => (),
+        }
+
+        let checkers = child.checkers();
+        if checkers.any() {
+            self.checks += 1;
+
+            let moved = match *m {
+                Move::Castle { king, rook } =>
+                    (if rook - king < 0 { Square::D1 } else { Square::F1 }).combine(rook),
+                _ => m.to(),
+            };
+
+            if checkers.without(moved).any() {
+                self.discovered_checks += 1;
+            }
+
+            if checkers.more_than_one() {
+                self.double_checks += 1;
+            }
+
+            if child.is_checkmate() {
+                self.checkmates += 1;
+            }
+        }
+    }
+
+    fn add(&mut self, other: &PerftStats) {
+        self.nodes += other.nodes;
+        self.captures += other.captures;
+        self.en_passant += other.en_passant;
+        self.castles += other.castles;
+        self.promotions += other.promotions;
+        self.checks += other.checks;
+        self.discovered_checks += other.discovered_checks;
+        self.double_checks += other.double_checks;
+        self.checkmates += other.checkmates;
+    }
+}
+
+fn stats_inner<P: Position + Clone>(pos: &P, stats: &mut [PerftStats]) {
+    if let Some((current, deeper)) = stats.split_first_mut() {
         let mut moves = MoveList::new();
         pos.legal_moves(&mut moves);
 
-        moves.iter().map(|m| {
-            let child = pos.clone().play(m).expect("legal move");
-            let nodes = perft(&child, depth - 1);
-            let uci: Uci = m.into();
-            println!("{} {} {}: {}", uci, m, depth - 1, nodes);
-            nodes
-        }).sum()
+        for m in &moves {
+            let mut child = pos.clone();
+            child.play_unchecked(m);
+            current.record(&child, m);
+            stats_inner(&child, deeper);
+        }
+    }
+}
+
+impl Default for Perft {
+    fn default() -> Perft {
+        Perft::new()
+    }
+}
+
+fn perft_inner<P: Position + Clone>(pos: &P, depth: u8, table: &Table) -> u64 {
+    if depth < 1 {
+        return 1;
+    }
+
+    let mut moves = MoveList::new();
+
+    if depth == 1 {
+        // Bulk counting at the leaves.
+        pos.legal_moves(&mut moves);
+        return moves.len() as u64;
+    }
+
+    let key = if table.is_empty() {
+        0
+    } else {
+        zobrist::hash(pos) ^ u64::from(depth).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+    };
+
+    if let Some(nodes) = table.probe(key) {
+        return nodes;
+    }
+
+    pos.legal_moves(&mut moves);
+
+    let nodes = moves.iter().map(|m| {
+        let mut child = pos.clone();
+        child.play_unchecked(m);
+        perft_inner(&child, depth - 1, table)
+    }).sum();
+
+    table.store(key, nodes);
+    nodes
+}
+
+/// A lockless hash table shared between perft workers. Keys combine the
+/// position hash and the remaining depth.
+struct Table {
+    entries: Vec<Entry>,
+}
+
+/// Stores the node count and the node count xored with the key, so that
+/// torn writes from concurrent threads are detected when probing.
+struct Entry {
+    check: AtomicU64,
+    nodes: AtomicU64,
+}
+
+impl Table {
+    fn with_size(bytes: usize) -> Table {
+        Table {
+            entries: (0..(bytes / mem::size_of::<Entry>())).map(|_| Entry {
+                check: AtomicU64::new(0),
+                nodes: AtomicU64::new(0),
+            }).collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn probe(&self, key: u64) -> Option<u64> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let entry = &self.entries[key as usize % self.entries.len()];
+        let nodes = entry.nodes.load(Ordering::Relaxed);
+        if nodes != 0 && entry.check.load(Ordering::Relaxed) ^ nodes == key {
+            Some(nodes)
+        } else {
+            None
+        }
+    }
+
+    fn store(&self, key: u64, nodes: u64) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let entry = &self.entries[key as usize % self.entries.len()];
+        entry.check.store(key ^ nodes, Ordering::Relaxed);
+        entry.nodes.store(nodes, Ordering::Relaxed);
+    }
+}
+
+/// A perft test suite.
+///
+/// Suites are line based. Blank lines and lines starting with `#` are
+/// ignored. Other lines are records:
+///
+/// * `variant <name>`: The variant of the suite (default `chess`).
+/// * `chess960`: Marks the suite as Chess960 (default standard chess).
+/// * `id <name>`: Names the next test position.
+/// * `epd <epd>`: Starts a new test position.
+/// * `perft <depth> <nodes>`: The expected perft result of the current test
+///   position.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use shakmaty::{Chess, Perft};
+/// use shakmaty::perft::Suite;
+///
+/// let suite: Suite = "
+///     id kiwipete
+///     epd r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -
+///     perft 1 48
+///     perft 2 2039
+/// ".parse()?;
+///
+/// assert_eq!(suite.variant, "chess");
+/// assert!(suite.run::<Chess>(&Perft::new(), 10_000).is_empty());
+/// #
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Suite {
+    pub variant: String,
+    pub chess960: bool,
+    pub records: Vec<Record>,
+}
+
+/// A test position of a perft [`Suite`].
+///
+/// [`Suite`]: struct.Suite.html
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Record {
+    pub id: Option<String>,
+    pub fen: Fen,
+    /// Pairs of depth and expected node count.
+    pub perfts: Vec<(u8, u64)>,
+}
+
+/// A failed test of a perft [`Suite`].
+///
+/// [`Suite`]: struct.Suite.html
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Failure {
+    IllegalPosition { id: Option<String>, error: PositionError },
+    WrongNodes { id: Option<String>, depth: u8, expected: u64, nodes: u64 },
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Failure::IllegalPosition { ref id, error } =>
+                write!(f, "{}: illegal position ({:?})", id.as_ref().map_or("-", |id| id), error),
+            Failure::WrongNodes { ref id, depth, expected, nodes } =>
+                write!(f, "{}: perft {} {} (expected {})", id.as_ref().map_or("-", |id| id), depth, nodes, expected),
+        }
+    }
+}
+
+/// Error when parsing an invalid perft [`Suite`].
+///
+/// [`Suite`]: struct.Suite.html
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct InvalidSuite {
+    /// The offending line (starting at `1`).
+    pub line: usize,
+}
+
+impl fmt::Display for InvalidSuite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid perft suite in line {}", self.line)
+    }
+}
+
+impl Error for InvalidSuite {
+    fn description(&self) -> &str {
+        "invalid perft suite"
+    }
+}
+
+impl Suite {
+    /// Parses a perft suite.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidSuite`] if a line is not a valid record, including
+    /// invalid EPDs and `perft` records before the first `epd`.
+    ///
+    /// [`InvalidSuite`]: struct.InvalidSuite.html
+    pub fn from_bytes(suite: &[u8]) -> Result<Suite, InvalidSuite> {
+        let mut result = Suite {
+            variant: "chess".to_owned(),
+            chess960: false,
+            records: Vec::new(),
+        };
+
+        let mut id = None;
+
+        for (idx, line) in suite.split(|ch| *ch == b'\n').enumerate() {
+            let err = InvalidSuite { line: idx + 1 };
+
+            let line = str::from_utf8(line).map_err(|_| err.clone())?.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, char::is_whitespace);
+            let keyword = parts.next().expect("splits have at least one part");
+            let value = parts.next().map(|v| v.trim());
+
+            match (keyword, value) {
+                ("variant", Some(variant)) => result.variant = variant.to_owned(),
+                ("chess960", None) => result.chess960 = true,
+                ("id", Some(value)) => id = Some(value.to_owned()),
+                ("epd", Some(epd)) => result.records.push(Record {
+                    id: id.take(),
+                    fen: epd.parse().map_err(|_| err.clone())?,
+                    perfts: Vec::new(),
+                }),
+                ("perft", Some(params)) => {
+                    let record = result.records.last_mut().ok_or_else(|| err.clone())?;
+                    let mut params = params.split_whitespace();
+                    let depth = params.next().and_then(|d| d.parse().ok()).ok_or_else(|| err.clone())?;
+                    let nodes = params.next().and_then(|n| n.parse().ok()).ok_or_else(|| err.clone())?;
+                    if params.next().is_some() {
+                        return Err(err);
+                    }
+                    record.perfts.push((depth, nodes));
+                },
+                _ => return Err(err),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Runs all perft tests of the suite with the given options, skipping
+    /// tests with more than `node_limit` expected nodes.
+    ///
+    /// It is the callers responsibility to choose a [`Position`] type `P`
+    /// matching [`variant`](#structfield.variant) and
+    /// [`chess960`](#structfield.chess960).
+    ///
+    /// Returns the failed tests, so an empty vector means success.
+    ///
+    /// [`Position`]: ../trait.Position.html
+    pub fn run<P>(&self, perft: &Perft, node_limit: u64) -> Vec<Failure>
+        where P: Position + Clone + Send + Sync + 'static
+    {
+        let mut failures = Vec::new();
+
+        for record in &self.records {
+            let pos: P = match record.fen.position() {
+                Ok(pos) => pos,
+                Err(error) => {
+                    failures.push(Failure::IllegalPosition { id: record.id.clone(), error });
+                    continue;
+                }
+            };
+
+            for &(depth, expected) in &record.perfts {
+                if expected <= node_limit {
+                    let nodes = perft.count(&pos, depth);
+                    if nodes != expected {
+                        failures.push(Failure::WrongNodes { id: record.id.clone(), depth, expected, nodes });
+                    }
+                }
+            }
+        }
+
+        failures
+    }
+}
+
+impl FromStr for Suite {
+    type Err = InvalidSuite;
+
+    fn from_str(suite: &str) -> Result<Suite, InvalidSuite> {
+        Suite::from_bytes(suite.as_bytes())
     }
 }
 
@@ -88,6 +654,108 @@ mod tests {
         assert_eq!(perft(&pos, 1), 20);
     }
 
+    #[test]
+    fn test_perft_opts() {
+        let fen: Fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        assert_eq!(Perft::new().count(&pos, 3), 97862);
+        assert_eq!(Perft::new().threads(3).count(&pos, 3), 97862);
+        assert_eq!(Perft::new().hash_size(1 << 16).count(&pos, 3), 97862);
+        assert_eq!(Perft::new().threads(3).hash_size(1 << 16).count(&pos, 3), 97862);
+    }
+
+    #[test]
+    fn test_stats() {
+        let fen: Fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq -".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        let stats = Perft::new().threads(2).stats(&pos, 3);
+        assert_eq!(stats[2], PerftStats {
+            nodes: 97862,
+            captures: 17102,
+            en_passant: 45,
+            castles: 3162,
+            promotions: 0,
+            checks: 993,
+            discovered_checks: 0,
+            double_checks: 0,
+            checkmates: 1,
+        });
+
+        let fen: Fen = "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - -".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        let stats = Perft::new().stats(&pos, 4);
+        assert_eq!(stats[3], PerftStats {
+            nodes: 43238,
+            captures: 3348,
+            en_passant: 123,
+            castles: 0,
+            promotions: 0,
+            checks: 1680,
+            discovered_checks: 106,
+            double_checks: 0,
+            checkmates: 17,
+        });
+    }
+
+    #[test]
+    fn test_divide() {
+        let pos = Chess::default();
+        let divided = Perft::new().threads(2).divide(&pos, 3);
+        assert_eq!(divided.len(), 20);
+        assert_eq!(divided.iter().map(|&(_, nodes)| nodes).sum::<u64>(), 8902);
+
+        for (m, nodes) in divided {
+            let child = pos.clone().play(&m).expect("legal move");
+            assert_eq!(nodes, perft(&child, 2) as u64);
+        }
+
+        assert!(Perft::new().divide(&pos, 0).is_empty());
+        assert_eq!(Perft::new().divide(&pos, 1).len(), 20);
+    }
+
+    #[test]
+    fn test_suite() {
+        let suite: Suite = "
+            variant chess
+            chess960
+
+            # Comment.
+            id start
+            epd rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -
+            perft 1 20
+            perft 2 401
+
+            epd 4k3/8/8/8/8/8/8/8 w - -
+            perft 1 0
+        ".parse().expect("valid suite");
+
+        assert!(suite.chess960);
+        assert_eq!(suite.records.len(), 2);
+        assert_eq!(suite.records[0].perfts, vec![(1, 20), (2, 401)]);
+
+        assert_eq!(suite.run::<Chess>(&Perft::new(), 400), vec![
+            Failure::IllegalPosition { id: None, error: PositionError::MISSING_KING },
+        ]);
+
+        assert_eq!(suite.run::<Chess>(&Perft::new(), 1000)[0], Failure::WrongNodes {
+            id: Some("start".to_owned()),
+            depth: 2,
+            expected: 401,
+            nodes: 400,
+        });
+    }
+
+    #[test]
+    fn test_invalid_suite() {
+        assert_eq!("perft 1 20".parse::<Suite>(), Err(InvalidSuite { line: 1 }));
+        assert_eq!("epd 8/8/8/8/8/8/8/8 w - -
+perft x".parse::<Suite>(), Err(InvalidSuite { line: 2 }));
+        assert_eq!("foo bar".parse::<Suite>(), Err(InvalidSuite { line: 1 }));
+    }
+
     #[cfg(nightly)]
     #[bench]
     fn bench_shallow_perft(b: &mut Bencher) {
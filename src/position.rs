@@ -21,6 +21,7 @@ use square::Square;
 use types::{Color, White, Black, Role, Piece, Move, Pockets, RemainingChecks};
 use setup::{Setup, Castling, CastlingSide, SwapTurn};
 use movelist::{MoveList, ArrayVecExt};
+use fen::Fen;
 
 use option_filter::OptionFilterExt;
 
@@ -57,6 +58,17 @@ bitflags! {
         const BAD_CASTLING_RIGHTS = 16;
         const INVALID_EP_SQUARE = 32;
         const OPPOSITE_CHECK = 64;
+        /// Castling rights on both sides for rooks that are not on opposite
+        /// sides of the king. Implies `BAD_CASTLING_RIGHTS`.
+        const KING_NOT_BETWEEN_ROOKS = 128;
+        /// More than two checkers, or two checkers that can not have been
+        /// delivered by a single move. Only in strict mode.
+        const IMPOSSIBLE_CHECK = 256;
+        /// More than 8 pawns of one color. Only in strict mode.
+        const TOO_MANY_PAWNS = 512;
+        /// More pieces of one color than could have been promoted from the
+        /// missing pawns. Only in strict mode.
+        const IMPOSSIBLE_MATERIAL = 1024;
     }
 }
 
@@ -82,13 +94,107 @@ impl PositionError {
     }
 }
 
+/// Reasons for an [`IllegalMove`].
+///
+/// [`IllegalMove`]: struct.IllegalMove.html
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum IllegalMoveReason {
+    /// There is no piece on the origin square.
+    NoPiece,
+    /// The piece on the origin square belongs to the opponent.
+    WrongColor,
+    /// The move does not match the board, e.g. the moving or captured
+    /// piece is not the one on the board.
+    Mismatch,
+    /// The target square is occupied by an own piece.
+    OwnPiece,
+    /// The piece can not move that way.
+    InvalidMovement,
+    /// Another piece is in the way.
+    PathBlocked,
+    /// The piece is pinned to its king.
+    Pinned,
+    /// The move leaves or puts the own king in check.
+    KingInCheck,
+    /// Castling without the corresponding castling rights.
+    NoCastlingRights,
+    /// Castling out of, through or into check.
+    CastlingThroughCheck,
+    /// A pawn reaches the back rank without promoting.
+    MissingPromotion,
+    /// Promotion of a piece other than a pawn, on a rank other than the
+    /// back rank, or to an invalid role.
+    InvalidPromotion,
+    /// The move is illegal for another reason, e.g. it is a null move or
+    /// not allowed in the variant.
+    Other,
+}
+
+impl IllegalMoveReason {
+    fn desc(self) -> &'static str {
+        match self {
+            IllegalMoveReason::NoPiece => "no piece on origin square",
+            IllegalMoveReason::WrongColor => "piece belongs to the opponent",
+            IllegalMoveReason::Mismatch => "move does not match the board",
+            IllegalMoveReason::OwnPiece => "target square occupied by own piece",
+            IllegalMoveReason::InvalidMovement => "piece can not move that way",
+            IllegalMoveReason::PathBlocked => "path is blocked",
+            IllegalMoveReason::Pinned => "piece is pinned",
+            IllegalMoveReason::KingInCheck => "king would be in check",
+            IllegalMoveReason::NoCastlingRights => "no castling rights",
+            IllegalMoveReason::CastlingThroughCheck => "castling out of, through or into check",
+            IllegalMoveReason::MissingPromotion => "missing promotion",
+            IllegalMoveReason::InvalidPromotion => "invalid promotion",
+            IllegalMoveReason::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for IllegalMoveReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.desc().fmt(f)
+    }
+}
+
 /// Error in case of illegal moves.
-#[derive(Debug)]
-pub struct IllegalMove;
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct IllegalMove {
+    /// Why the move is illegal.
+    pub reason: IllegalMoveReason,
+}
+
+impl IllegalMove {
+    /// Explains why `m` is not legal in `pos`.
+    ///
+    /// The result is meaningless if the move is actually legal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Square, Role, Move, IllegalMove, IllegalMoveReason};
+    ///
+    /// let pos = Chess::default();
+    /// let m = Move::Normal {
+    ///     role: Role::Bishop,
+    ///     from: Square::C1,
+    ///     capture: None,
+    ///     to: Square::E3,
+    ///     promotion: None,
+    /// };
+    ///
+    /// assert_eq!(IllegalMove::explain(&pos, &m).reason, IllegalMoveReason::PathBlocked);
+    /// ```
+    pub fn explain<P: Position>(pos: &P, m: &Move) -> IllegalMove {
+        IllegalMove { reason: explain(pos, m) }
+    }
+}
 
 impl fmt::Display for IllegalMove {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        "illegal move".fmt(f)
+        match self.reason {
+            IllegalMoveReason::Other => "illegal move".fmt(f),
+            reason => write!(f, "illegal move ({})", reason),
+        }
     }
 }
 
@@ -112,6 +218,23 @@ pub trait Position: Setup {
     /// [`PositionError`]: enum.PositionError.html
     fn from_setup<S: Setup>(setup: &S) -> Result<Self, PositionError> where Self: Sized;
 
+    /// Set up a position, additionally rejecting setups that can not be
+    /// reached in a game, like impossible checks or too much material.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PositionError`] if the setup is not legal, including
+    /// `IMPOSSIBLE_CHECK`, `TOO_MANY_PAWNS` and `IMPOSSIBLE_MATERIAL`.
+    ///
+    /// [`PositionError`]: enum.PositionError.html
+    fn from_setup_strict<S: Setup>(setup: &S) -> Result<Self, PositionError> where Self: Sized {
+        let errors = validate_strict(setup);
+        match Self::from_setup(setup) {
+            Ok(pos) => errors.into_result(pos),
+            Err(lenient) => Err(lenient | errors),
+        }
+    }
+
     /// Swap turns. This is sometimes called "playing a null move".
     ///
     /// # Errors
@@ -282,7 +405,8 @@ pub trait Position: Setup {
     ///
     /// # Errors
     ///
-    /// Returns [`IllegalMove`] if the move is not legal in the position.
+    /// Returns [`IllegalMove`] if the move is not legal in the position,
+    /// explaining why.
     ///
     /// [`IllegalMove`]: struct.IllegalMove.html
     fn play(mut self, m: &Move) -> Result<Self, IllegalMove>
@@ -292,7 +416,7 @@ pub trait Position: Setup {
             self.play_unchecked(m);
             Ok(self)
         } else {
-            Err(IllegalMove {})
+            Err(IllegalMove::explain(&self, m))
         }
     }
 
@@ -330,6 +454,63 @@ impl Default for Chess {
     }
 }
 
+impl Chess {
+    /// Sets up a starting position with the given arrangements of pieces on
+    /// the first rank of White and the eighth rank of Black (both from the
+    /// a-file to the h-file), pawns in front of them and White to move.
+    ///
+    /// Both sides get castling rights for their outermost rooks. This
+    /// supports Double Fischer Random Chess, where the back ranks of White
+    /// and Black are chosen independently.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PositionError`] if the position is not legal, for example
+    /// `KING_NOT_BETWEEN_ROOKS` if a king is not between its rooks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Chess, Role, PositionError};
+    /// use shakmaty::Role::*;
+    /// use shakmaty::fen::{self, FenOpts};
+    ///
+    /// let pos = Chess::from_back_ranks(&[Rook, Knight, Bishop, Queen, King, Bishop, Knight, Rook],
+    ///                                  &[Bishop, Bishop, Queen, Knight, Knight, Rook, King, Rook])
+    ///     .expect("legal position");
+    ///
+    /// assert_eq!(fen::fen(&pos, FenOpts::new().shredder(true)),
+    ///            "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAhf - 0 1");
+    ///
+    /// let err = Chess::from_back_ranks(&[Rook, Rook, King, Knight, Knight, Queen, Bishop, Bishop],
+    ///                                  &[Rook, Knight, Bishop, Queen, King, Bishop, Knight, Rook])
+    ///     .expect_err("illegal position");
+    ///
+    /// assert!(err.contains(PositionError::KING_NOT_BETWEEN_ROOKS));
+    /// ```
+    ///
+    /// [`PositionError`]: struct.PositionError.html
+    pub fn from_back_ranks(white: &[Role; 8], black: &[Role; 8]) -> Result<Chess, PositionError> {
+        let mut fen = Fen::empty();
+
+        for &(color, back_rank) in &[(White, white), (Black, black)] {
+            for (file, &role) in back_rank.iter().enumerate() {
+                let sq = Square::from_coords(file as i8, color.fold(0, 7)).expect("valid coords");
+                fen.board.set_piece_at(sq, role.of(color), false);
+
+                let pawn = Square::from_coords(file as i8, color.fold(1, 6)).expect("valid coords");
+                fen.board.set_piece_at(pawn, color.pawn(), false);
+            }
+
+            let rooks = fen.board.by_piece(color.rook()) & Bitboard::relative_rank(color, 0);
+            fen.castling_rights.extend(rooks.first());
+            fen.castling_rights.extend(rooks.last());
+        }
+
+        fen.position()
+    }
+}
+
 impl Setup for Chess {
     fn board(&self) -> &Board { &self.board }
     fn pockets(&self) -> Option<&Pockets> { None }
@@ -351,7 +532,13 @@ impl Position for Chess {
     fn from_setup<S: Setup>(setup: &S) -> Result<Chess, PositionError> {
         let (castling, errors) = match Castling::from_setup(setup) {
             Ok(castling) => (castling, PositionError::empty()),
-            Err(castling) => (castling, PositionError::BAD_CASTLING_RIGHTS),
+            Err(castling) => {
+                let mut errors = PositionError::BAD_CASTLING_RIGHTS;
+                if Castling::king_not_between_rooks(setup) {
+                    errors |= PositionError::KING_NOT_BETWEEN_ROOKS;
+                }
+                (castling, errors)
+            },
         };
 
         let pos = Chess {
@@ -848,7 +1035,7 @@ fn gen_en_passant(board: &Board, turn: Color, ep_square: Option<Square>, moves:
     found
 }
 
-fn slider_blockers(board: &Board, enemy: Bitboard, king: Square) -> Bitboard {
+pub fn slider_blockers(board: &Board, enemy: Bitboard, king: Square) -> Bitboard {
     let snipers = (attacks::rook_attacks(king, Bitboard(0)) & board.rooks_and_queens()) |
                   (attacks::bishop_attacks(king, Bitboard(0)) & board.bishops_and_queens());
 
@@ -882,6 +1069,155 @@ fn is_safe<P: Position>(pos: &P, king: Square, m: &Move, blockers: Bitboard) ->
     }
 }
 
+fn explain<P: Position>(pos: &P, m: &Move) -> IllegalMoveReason {
+    let board = pos.board();
+    let turn = pos.turn();
+    let back_rank = turn.fold(7, 0);
+
+    let (role, from, to) = match *m {
+        Move::Normal { role, from, to, .. } => (role, from, to),
+        Move::EnPassant { from, to } => (Role::Pawn, from, to),
+        Move::Castle { king, .. } => (Role::King, king, king),
+        Move::Put { .. } => return IllegalMoveReason::Other,
+    };
+
+    match board.piece_at(from) {
+        None => return IllegalMoveReason::NoPiece,
+        Some(piece) if piece.color != turn => return IllegalMoveReason::WrongColor,
+        Some(piece) if piece.role != role => return IllegalMoveReason::Mismatch,
+        Some(_) => (),
+    }
+
+    match *m {
+        Move::Normal { capture, promotion, .. } => {
+            if pos.us().contains(to) {
+                return IllegalMoveReason::OwnPiece;
+            } else if capture != board.role_at(to) {
+                return IllegalMoveReason::Mismatch;
+            }
+
+            if role == Role::Pawn {
+                let distance = turn.fold(1, -1) * (to.rank() - from.rank());
+                if from.file() == to.file() {
+                    if distance != 1 && (distance != 2 || from.rank() != turn.fold(1, 6)) {
+                        return IllegalMoveReason::InvalidMovement;
+                    } else if capture.is_some() || (attacks::between(from, to) & board.occupied()).any() {
+                        return IllegalMoveReason::PathBlocked;
+                    }
+                } else if capture.is_none() || !attacks::pawn_attacks(turn, from).contains(to) {
+                    return IllegalMoveReason::InvalidMovement;
+                }
+            } else if !attacks::attacks(from, role.of(turn), Bitboard(0)).contains(to) {
+                return IllegalMoveReason::InvalidMovement;
+            } else if (attacks::between(from, to) & board.occupied()).any() {
+                return IllegalMoveReason::PathBlocked;
+            }
+
+            match promotion {
+                None if role == Role::Pawn && to.rank() == back_rank =>
+                    return IllegalMoveReason::MissingPromotion,
+                Some(_) if role != Role::Pawn || to.rank() != back_rank =>
+                    return IllegalMoveReason::InvalidPromotion,
+                _ => (),
+            }
+        },
+        Move::EnPassant { .. } => {
+            if pos.ep_square() != Some(to) || !attacks::pawn_attacks(turn, from).contains(to) {
+                return IllegalMoveReason::InvalidMovement;
+            }
+        },
+        Move::Castle { king, rook } => {
+            if !pos.castling_rights().contains(rook) || board.piece_at(rook) != Some(turn.rook()) {
+                return IllegalMoveReason::NoCastlingRights;
+            }
+
+            let side = if rook < king { CastlingSide::QueenSide } else { CastlingSide::KingSide };
+            let king_to = side.king_to(turn);
+            let rook_to = side.rook_to(turn);
+
+            let path = (attacks::between(king, king_to).with(king_to) |
+                        attacks::between(rook, rook_to).with(rook_to)).without(king).without(rook);
+            if (path & board.occupied()).any() {
+                return IllegalMoveReason::PathBlocked;
+            }
+
+            let king_path = attacks::between(king, king_to).with(king_to).with(king);
+            let occupied = board.occupied().without(king).without(rook);
+            for sq in king_path {
+                if pos.king_attackers(sq, !turn, occupied).any() {
+                    return IllegalMoveReason::CastlingThroughCheck;
+                }
+            }
+
+            return IllegalMoveReason::Other;
+        },
+        Move::Put { .. } => (),
+    }
+
+    let king = match board.king_of(turn) {
+        Some(king) => king,
+        None => return IllegalMoveReason::Other,
+    };
+
+    if from == king {
+        return IllegalMoveReason::KingInCheck;
+    } else if (slider_blockers(board, pos.them(), king) & pos.us()).contains(from) &&
+              !attacks::aligned(from, to, king) {
+        return IllegalMoveReason::Pinned;
+    }
+
+    let mut after = board.clone();
+    after.discard_piece_at(from);
+    after.set_piece_at(to, role.of(turn), false);
+    if let Move::EnPassant { .. } = *m {
+        after.discard_piece_at(Square::from_coords(to.file(), from.rank()).expect("valid coords"));
+    }
+    if after.attacks_to(king, !turn, after.occupied()).any() {
+        return IllegalMoveReason::KingInCheck;
+    }
+
+    match m.promotion() {
+        Some(Role::Pawn) | Some(Role::King) => IllegalMoveReason::InvalidPromotion,
+        _ => IllegalMoveReason::Other,
+    }
+}
+
+fn validate_strict<S: Setup>(setup: &S) -> PositionError {
+    let mut errors = PositionError::empty();
+    let board = setup.board();
+
+    if let Some(king) = board.king_of(setup.turn()) {
+        let checkers = board.attacks_to(king, !setup.turn(), board.occupied());
+        if checkers.count() > 2 {
+            errors |= PositionError::IMPOSSIBLE_CHECK;
+        } else if let (Some(a), Some(b)) = (checkers.first(), checkers.last()) {
+            // A double check is always a discovered check by a slider.
+            if a != b && ((checkers & board.sliders()).is_empty() || attacks::aligned(a, b, king)) {
+                errors |= PositionError::IMPOSSIBLE_CHECK;
+            }
+        }
+    }
+
+    for &color in &[White, Black] {
+        let ours = board.by_color(color);
+        let pawns = (board.pawns() & ours).count();
+
+        // Pieces beyond the initial set must have been promoted.
+        let promoted = (board.knights() & ours).count().saturating_sub(2) +
+                       (board.bishops() & ours).count().saturating_sub(2) +
+                       (board.rooks() & ours).count().saturating_sub(2) +
+                       (board.queens() & ours).count().saturating_sub(1);
+
+        if pawns > 8 {
+            errors |= PositionError::TOO_MANY_PAWNS;
+        } else if pawns + promoted > 8 {
+            errors |= PositionError::IMPOSSIBLE_MATERIAL;
+        }
+    }
+
+    errors
+}
+
 fn filter_san_candidates(role: Role, to: Square, moves: &mut MoveList) {
     moves.retain(|m| match *m {
         Move::Normal { role: r, to: t, .. } | Move::Put { role: r, to: t } =>
@@ -894,7 +1230,6 @@ fn filter_san_candidates(role: Role, to: Square, moves: &mut MoveList) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use fen::Fen;
 
     #[cfg(nightly)]
     use test::Bencher;
@@ -991,4 +1326,81 @@ mod tests {
 
         assert_eq!(moves.len(), 1);
     }
+
+    #[test]
+    fn test_strict_validation() {
+        let strict = |fen: &str| {
+            let fen: Fen = fen.parse().expect("valid fen");
+            let lenient = Chess::from_setup(&fen).err().unwrap_or(PositionError::empty());
+            let strict = Chess::from_setup_strict(&fen).err().unwrap_or(PositionError::empty());
+            assert!(strict.contains(lenient));
+            strict - lenient
+        };
+
+        assert_eq!(strict("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), PositionError::empty());
+
+        // Double checks.
+        assert_eq!(strict("4k3/8/8/8/8/5n2/3p4/4K3 w - - 0 1"), PositionError::IMPOSSIBLE_CHECK);
+        assert_eq!(strict("4k3/8/8/8/4r3/8/8/r3K3 w - - 0 1"), PositionError::empty());
+        assert_eq!(strict("4k3/8/8/8/8/8/8/r3K2r w - - 0 1"), PositionError::IMPOSSIBLE_CHECK);
+        assert_eq!(strict("4k3/8/8/b7/4r3/5n2/8/4K3 w - - 0 1"), PositionError::IMPOSSIBLE_CHECK);
+
+        // Material.
+        assert_eq!(strict("4k3/8/8/8/8/P7/PPPPPPPP/4K3 w - - 0 1"), PositionError::TOO_MANY_PAWNS);
+        assert_eq!(strict("4k3/8/8/8/8/8/PPPPPPPP/QQ2K3 w - - 0 1"), PositionError::IMPOSSIBLE_MATERIAL);
+        assert_eq!(strict("4k3/8/8/8/8/8/1PPPPPPP/QQ2K3 w - - 0 1"), PositionError::empty());
+        assert_eq!(strict("4k3/8/8/8/8/8/8/NNNNKNNN w - - 0 1"), PositionError::empty());
+    }
+
+    #[test]
+    fn test_explain_illegal() {
+        use uci::Uci;
+
+        let reason = |fen: &str, uci: &str| {
+            let pos: Chess = fen.parse::<Fen>()
+                .expect("valid fen")
+                .position()
+                .expect("valid position");
+            uci.parse::<Uci>().expect("valid uci").to_move(&pos).expect_err("illegal").reason
+        };
+
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+        assert_eq!(reason(start, "e3e4"), IllegalMoveReason::NoPiece);
+        assert_eq!(reason(start, "e7e5"), IllegalMoveReason::WrongColor);
+        assert_eq!(reason(start, "g1g3"), IllegalMoveReason::InvalidMovement);
+        assert_eq!(reason(start, "e2e5"), IllegalMoveReason::InvalidMovement);
+        assert_eq!(reason(start, "e2d3"), IllegalMoveReason::InvalidMovement);
+        assert_eq!(reason(start, "f1c4"), IllegalMoveReason::PathBlocked);
+        assert_eq!(reason(start, "d1d2"), IllegalMoveReason::OwnPiece);
+        assert_eq!(reason(start, "e2e4q"), IllegalMoveReason::InvalidPromotion);
+        assert_eq!(reason(start, "g1f3q"), IllegalMoveReason::InvalidPromotion);
+
+        let pinned = "4k3/8/8/b7/8/2N5/8/4K3 w - - 0 1";
+        assert_eq!(reason(pinned, "c3e4"), IllegalMoveReason::Pinned);
+
+        let check = "4k3/8/8/8/8/8/P7/4K2r w - - 0 1";
+        assert_eq!(reason(check, "a2a3"), IllegalMoveReason::KingInCheck);
+        assert_eq!(reason(check, "e1f1"), IllegalMoveReason::KingInCheck);
+
+        let promotion = "8/P3k3/8/8/8/8/8/4K3 w - - 0 1";
+        assert_eq!(reason(promotion, "a7a8"), IllegalMoveReason::MissingPromotion);
+        assert_eq!(reason(promotion, "a7a8k"), IllegalMoveReason::InvalidPromotion);
+
+        assert_eq!(reason("4k3/8/8/8/8/8/8/R3K2R w Q - 0 1", "e1g1"), IllegalMoveReason::NoCastlingRights);
+        assert_eq!(reason("4k3/8/8/8/8/8/8/R3KB1R w KQ - 0 1", "e1g1"), IllegalMoveReason::PathBlocked);
+        assert_eq!(reason("4kr2/8/8/8/8/8/8/R3K2R w KQ - 0 1", "e1g1"), IllegalMoveReason::CastlingThroughCheck);
+
+        let pos: Chess = pinned.parse::<Fen>()
+            .expect("valid fen")
+            .position()
+            .expect("valid position");
+        let err = pos.play(&Move::Normal {
+            role: Role::Knight,
+            from: Square::C3,
+            capture: None,
+            to: Square::E4,
+            promotion: None,
+        }).expect_err("pinned");
+        assert_eq!(err.to_string(), "illegal move (piece is pinned)");
+    }
 }
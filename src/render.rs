@@ -0,0 +1,308 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Render boards as ASCII or Unicode text.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Board, Color};
+//! use shakmaty::render::RenderOpts;
+//!
+//! let board = Board::new();
+//!
+//! assert_eq!(RenderOpts::new().board(&board), "\
+//! r n b q k b n r
+//! p p p p p p p p
+//! . . . . . . . .
+//! . . . . . . . .
+//! . . . . . . . .
+//! . . . . . . . .
+//! P P P P P P P P
+//! R N B Q K B N R
+//! ");
+//!
+//! assert_eq!(RenderOpts::new().figurines(true)
+//!                             .orientation(Color::Black)
+//!                             .coordinates(true)
+//!                             .board(&board), "\
+//! 1 ♖ ♘ ♗ ♔ ♕ ♗ ♘ ♖
+//! 2 ♙ ♙ ♙ ♙ ♙ ♙ ♙ ♙
+//! 3 · · · · · · · ·
+//! 4 · · · · · · · ·
+//! 5 · · · · · · · ·
+//! 6 · · · · · · · ·
+//! 7 ♟ ♟ ♟ ♟ ♟ ♟ ♟ ♟
+//! 8 ♜ ♞ ♝ ♚ ♛ ♝ ♞ ♜
+//!   h g f e d c b a
+//! ");
+//! ```
+
+use square::Square;
+use types::{Color, Piece, Move, ROLES};
+use bitboard::Bitboard;
+use board::Board;
+use setup::Setup;
+
+/// Text rendering options.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct RenderOpts {
+    figurines: bool,
+    orientation: Color,
+    coordinates: bool,
+    highlights: Bitboard,
+    last_move: Bitboard,
+    pockets: bool,
+}
+
+impl RenderOpts {
+    /// ASCII letters from White's point of view, without coordinates.
+    pub fn new() -> RenderOpts {
+        RenderOpts {
+            figurines: false,
+            orientation: Color::White,
+            coordinates: false,
+            highlights: Bitboard(0),
+            last_move: Bitboard(0),
+            pockets: true,
+        }
+    }
+
+    /// Decide if pieces should be displayed as Unicode figurines like `♘`
+    /// instead of letters like `N`.
+    pub fn figurines(&mut self, figurines: bool) -> &mut RenderOpts {
+        self.figurines = figurines;
+        self
+    }
+
+    /// Show the board from the point of view of `orientation`.
+    pub fn orientation(&mut self, orientation: Color) -> &mut RenderOpts {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Decide if rank and file labels should be displayed.
+    pub fn coordinates(&mut self, coordinates: bool) -> &mut RenderOpts {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Highlight squares, e.g. `[N]`.
+    pub fn highlights(&mut self, highlights: Bitboard) -> &mut RenderOpts {
+        self.highlights = highlights;
+        self
+    }
+
+    /// Mark the origin and target squares of a move, e.g. `(N)`.
+    pub fn last_move(&mut self, m: &Move) -> &mut RenderOpts {
+        self.last_move = Bitboard::from_square(m.to());
+        if let Some(from) = m.from() {
+            self.last_move.add(from);
+        }
+        self
+    }
+
+    /// Decide if pockets should be displayed for variants that have them.
+    pub fn pockets(&mut self, pockets: bool) -> &mut RenderOpts {
+        self.pockets = pockets;
+        self
+    }
+
+    /// See [`board()`](fn.board.html).
+    pub fn board(&self, board: &Board) -> String {
+        self::board(board, self)
+    }
+
+    /// See [`setup()`](fn.setup.html).
+    pub fn setup(&self, setup: &Setup) -> String {
+        self::setup(setup, self)
+    }
+
+    fn piece_char(&self, piece: Piece) -> char {
+        if self.figurines { piece.figurine() } else { piece.char() }
+    }
+
+    fn pocket(&self, setup: &Setup, color: Color) -> Option<String> {
+        if !self.pockets {
+            return None;
+        }
+
+        setup.pockets().map(|pockets| {
+            let mut line = String::new();
+            if self.coordinates {
+                line.push_str("  ");
+            }
+            line.push('[');
+            for &role in &ROLES {
+                let piece = role.of(color);
+                for _ in 0..pockets.by_piece(piece) {
+                    line.push(self.piece_char(piece));
+                }
+            }
+            line.push_str("]\n");
+            line
+        })
+    }
+}
+
+impl Default for RenderOpts {
+    fn default() -> RenderOpts {
+        RenderOpts::new()
+    }
+}
+
+/// Render a board as text, one line per rank.
+///
+/// If any squares are highlighted or marked as part of the last move, each
+/// square is rendered three characters wide, e.g. `[N]`, `(N)` or ` N `.
+/// Otherwise squares are separated by single spaces. Lines have no trailing
+/// spaces.
+pub fn board(board: &Board, opts: &RenderOpts) -> String {
+    let marked = (opts.highlights | opts.last_move).any();
+    let mut text = String::new();
+
+    for row in 0..8 {
+        let rank = opts.orientation.fold(7 - row, row);
+
+        if opts.coordinates {
+            text.push((b'1' + rank as u8) as char);
+            text.push(' ');
+        }
+
+        for col in 0..8 {
+            let file = opts.orientation.fold(col, 7 - col);
+            let sq = Square::from_coords(file, rank).expect("valid coords");
+
+            let ch = board.piece_at(sq).map_or(if opts.figurines { '·' } else { '.' }, |piece| {
+                opts.piece_char(piece)
+            });
+
+            if marked {
+                let (open, close) = if opts.highlights.contains(sq) {
+                    ('[', ']')
+                } else if opts.last_move.contains(sq) {
+                    ('(', ')')
+                } else {
+                    (' ', ' ')
+                };
+                text.push(open);
+                text.push(ch);
+                text.push(close);
+            } else {
+                text.push(ch);
+                if col < 7 {
+                    text.push(' ');
+                }
+            }
+        }
+
+        end_line(&mut text);
+    }
+
+    if opts.coordinates {
+        text.push_str("  ");
+        for col in 0..8 {
+            let file = opts.orientation.fold(col, 7 - col);
+            if marked {
+                text.push(' ');
+            }
+            text.push((b'a' + file as u8) as char);
+            if marked || col < 7 {
+                text.push(' ');
+            }
+        }
+        end_line(&mut text);
+    }
+
+    text
+}
+
+fn end_line(text: &mut String) {
+    let len = text.trim_right_matches(' ').len();
+    text.truncate(len);
+    text.push('\n');
+}
+
+/// Render a setup as text. In addition to the [board](fn.board.html) this
+/// shows the pockets of both sides (in Crazyhouse), the opponent's above and
+/// the own below the board.
+pub fn setup(setup: &Setup, opts: &RenderOpts) -> String {
+    let mut text = String::new();
+
+    if let Some(pocket) = opts.pocket(setup, !opts.orientation) {
+        text.push_str(&pocket);
+    }
+
+    text.push_str(&board(setup.board(), opts));
+
+    if let Some(pocket) = opts.pocket(setup, opts.orientation) {
+        text.push_str(&pocket);
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::Role;
+    use fen::Fen;
+
+    #[test]
+    fn test_highlights() {
+        let fen: Fen = "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1".parse().expect("valid fen");
+        let m = Move::Normal {
+            role: Role::Pawn,
+            from: Square::E2,
+            capture: None,
+            to: Square::E4,
+            promotion: None,
+        };
+
+        let text = RenderOpts::new()
+            .highlights(Bitboard::from_square(Square::E8))
+            .last_move(&m)
+            .coordinates(true)
+            .board(&fen.board);
+
+        assert_eq!(text.lines().next(), Some("8  .  .  .  . [k] .  .  ."));
+        assert_eq!(text.lines().nth(4), Some("4  .  .  .  . (.) .  .  ."));
+        assert_eq!(text.lines().nth(6), Some("2  .  .  .  . (P) .  .  ."));
+        assert_eq!(text.lines().nth(8), Some("   a  b  c  d  e  f  g  h"));
+        assert!(text.lines().all(|line| !line.ends_with(' ')));
+
+        let text = RenderOpts::new()
+            .highlights(Bitboard::from_square(Square::H1))
+            .board(&fen.board);
+        assert_eq!(text.lines().last(), Some(" .  .  .  .  K  .  . [.]"));
+    }
+
+    #[test]
+    fn test_pockets() {
+        let fen: Fen = "4k3/8/8/8/8/8/8/4K3[NNp] b - - 0 1".parse().expect("valid fen");
+
+        let text = RenderOpts::new().setup(&fen);
+        assert!(text.starts_with("[p]\n"));
+        assert!(text.ends_with("[NN]\n"));
+
+        let text = RenderOpts::new().orientation(Color::Black).setup(&fen);
+        assert!(text.starts_with("[NN]\n"));
+        assert!(text.ends_with("[p]\n"));
+
+        let text = RenderOpts::new().pockets(false).setup(&fen);
+        assert!(!text.contains('['));
+    }
+}
@@ -91,9 +91,11 @@
 
 use square::Square;
 use types::{Move, Role};
+use bitboard::Bitboard;
 use setup::CastlingSide;
-use position::{Position, Outcome};
+use position::{Position, Outcome, slider_blockers};
 use movelist::MoveList;
+use attacks;
 
 use std::fmt;
 use option_filter::OptionFilterExt;
@@ -101,20 +103,28 @@ use std::str::FromStr;
 use std::error::Error;
 
 /// Error when parsing a syntactially invalid SAN.
-#[derive(Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq)]
 pub struct InvalidSan {
-    _priv: (),
+    offset: usize,
+}
+
+impl InvalidSan {
+    /// The byte offset of the first unexpected character, or the length of
+    /// the input if it ended prematurely.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
 }
 
 impl fmt::Debug for InvalidSan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("InvalidSan").finish()
+        f.debug_struct("InvalidSan").field("offset", &self.offset).finish()
     }
 }
 
 impl fmt::Display for InvalidSan {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        "invalid san".fmt(f)
+        write!(f, "invalid san at byte {}", self.offset)
     }
 }
 
@@ -124,31 +134,57 @@ impl Error for InvalidSan {
     }
 }
 
-impl From<()> for InvalidSan {
-    fn from(_: ()) -> InvalidSan {
-        InvalidSan { _priv: () }
-    }
-}
-
-/// `IllegalSan` or `AmbiguousSan`.
-#[derive(Debug)]
+/// Error when a SAN can not be converted to a legal move.
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub enum SanError {
-    IllegalSan,
-    AmbiguousSan,
+    /// The SAN is not syntactically valid.
+    InvalidSan(InvalidSan),
+    /// There is no matching legal move.
+    IllegalSan {
+        /// Pieces that match the SAN and could reach the target square, but
+        /// are pinned to their king.
+        pinned: Bitboard,
+        /// Legal moves that match the SAN except for the capture indicator.
+        capture_mismatch: Vec<Move>,
+    },
+    /// There are multiple matching legal moves.
+    AmbiguousSan {
+        /// All legal moves that match the SAN.
+        candidates: Vec<Move>,
+    },
 }
 
 impl SanError {
+    fn illegal() -> SanError {
+        SanError::IllegalSan { pinned: Bitboard(0), capture_mismatch: Vec::new() }
+    }
+
     fn desc(&self) -> &str {
         match *self {
-            SanError::IllegalSan => "illegal san",
-            SanError::AmbiguousSan => "ambiguous san",
+            SanError::InvalidSan(_) => "invalid san",
+            SanError::IllegalSan { .. } => "illegal san",
+            SanError::AmbiguousSan { .. } => "ambiguous san",
         }
     }
 }
 
 impl fmt::Display for SanError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.desc().fmt(f)
+        match *self {
+            SanError::InvalidSan(ref err) => err.fmt(f),
+            SanError::IllegalSan { pinned, .. } if pinned.any() =>
+                write!(f, "illegal san (pinned piece on {})", pinned.first().expect("pinned")),
+            SanError::IllegalSan { ref capture_mismatch, .. } if !capture_mismatch.is_empty() =>
+                write!(f, "illegal san (capture mismatch, did you mean {}?)", capture_mismatch[0]),
+            SanError::AmbiguousSan { ref candidates } => {
+                write!(f, "ambiguous san (candidates:")?;
+                for candidate in candidates {
+                    write!(f, " {}", candidate)?;
+                }
+                write!(f, ")")
+            },
+            _ => self.desc().fmt(f),
+        }
     }
 }
 
@@ -158,6 +194,12 @@ impl Error for SanError {
     }
 }
 
+impl From<InvalidSan> for SanError {
+    fn from(err: InvalidSan) -> SanError {
+        SanError::InvalidSan(err)
+    }
+}
+
 
 /// A move in Standard Algebraic Notation.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -204,6 +246,8 @@ impl San {
             san = &san[0..(san.len() - 1)];
         }
 
+        let invalid = |offset: usize| InvalidSan { offset };
+
         if san == b"--" {
             Ok(San::Null)
         } else if san == b"O-O" {
@@ -213,63 +257,58 @@ impl San {
         } else if san.len() == 3 && san[0] == b'@' {
             Ok(San::Put {
                 role: Role::Pawn,
-                to: Square::from_bytes(&san[1..]).map_err(|_| ())?
+                to: square_at(san, 1)?,
             })
         } else if san.len() == 4 && san[1] == b'@' {
             Ok(San::Put {
-                role: Role::from_char(san[0] as char).ok_or(())?,
-                to: Square::from_bytes(&san[2..]).map_err(|_| ())?
+                role: Role::from_char(san[0] as char).ok_or_else(|| invalid(0))?,
+                to: square_at(san, 2)?,
             })
         } else {
-            let mut chars = san.iter().cloned();
-
-            let (role, next) = {
-                let ch = chars.next().ok_or(())?;
-                if ch >= b'a' {
-                    (Role::Pawn, ch)
-                } else {
-                    (Role::from_char(ch as char).ok_or(())?, chars.next().ok_or(())?)
-                }
+            let (role, mut i) = match san.first() {
+                Some(&ch) if ch >= b'a' => (Role::Pawn, 0),
+                Some(&ch) => (Role::from_char(ch as char).ok_or_else(|| invalid(0))?, 1),
+                None => return Err(invalid(0)),
             };
 
-            let (file, next) = if b'a' <= next && next <= b'h' {
-                (Some((next - b'a') as i8), chars.next().ok_or(())?)
-            } else {
-                (None, next)
-            };
+            let next = *san.get(i).ok_or_else(|| invalid(i))?;
+            let file = file_from_char(next);
+            if file.is_some() {
+                i += 1;
+                san.get(i).ok_or_else(|| invalid(i))?;
+            }
 
-            let (rank, next) = if b'1' <= next && next <= b'8' {
-                (Some((next - b'1') as i8), chars.next())
-            } else {
-                (None, Some(next))
-            };
+            let rank = san.get(i).cloned().and_then(rank_from_char);
+            if rank.is_some() {
+                i += 1;
+            }
 
             // This section is safe, because coordinates are already validated
             // by file_from_char or rank_from_char.
-            let (capture, file, rank, to, next) = if let Some(next) = next {
-                if next == b'x' {
-                    let to_file = chars.next().and_then(file_from_char).ok_or(())?;
-                    let to_rank = chars.next().and_then(rank_from_char).ok_or(())?;
-                    let square = unsafe { Square::from_coords_unchecked(to_file, to_rank) };
-                    (true, file, rank, square, chars.next())
-                } else if next == b'=' {
-                    let square = unsafe { Square::from_coords_unchecked(file.ok_or(())?, rank.ok_or(())?) };
-                    (false, None, None, square, Some(b'='))
-                } else {
-                    let to_file = file_from_char(next).ok_or(())?;
-                    let to_rank = chars.next().and_then(rank_from_char).ok_or(())?;
-                    let square = unsafe { Square::from_coords_unchecked(to_file, to_rank) };
-                    (false, file, rank, square, chars.next())
-                }
-            } else {
-                let square = unsafe { Square::from_coords_unchecked(file.ok_or(())?, rank.ok_or(())?) };
-                (false, None, None, square, None)
+            let (capture, file, rank, to) = match san.get(i) {
+                Some(&b'x') => {
+                    let square = square_at(san, i + 1)?;
+                    i += 3;
+                    (true, file, rank, square)
+                },
+                Some(&b'=') | None => {
+                    let square = unsafe {
+                        Square::from_coords_unchecked(file.ok_or_else(|| invalid(i))?,
+                                                      rank.ok_or_else(|| invalid(i))?)
+                    };
+                    (false, None, None, square)
+                },
+                Some(_) => {
+                    let square = square_at(san, i)?;
+                    i += 2;
+                    (false, file, rank, square)
+                },
             };
 
-            let promotion = match next {
-                Some(b'=') =>
-                    Some(chars.next().and_then(|r| Role::from_char(r as char)).ok_or(())?),
-                Some(_) => return Err(InvalidSan { _priv: () }),
+            let promotion = match san.get(i) {
+                Some(&b'=') =>
+                    Some(san.get(i + 1).and_then(|&r| Role::from_char(r as char)).ok_or_else(|| invalid(i + 1))?),
+                Some(_) => return Err(invalid(i)),
                 None => None,
             };
 
@@ -277,6 +316,80 @@ impl San {
         }
     }
 
+    /// Parses a SAN, accepting common variations found in real-world
+    /// input:
+    ///
+    /// * lowercase piece letters like `nf3`,
+    /// * castling with zeros or lowercase letters like `0-0` or `o-o-o`,
+    /// * pawn captures without `x` like `ed5`,
+    /// * promotions without `=` like `e8Q`,
+    /// * trailing annotations like `!?` or `+/-`,
+    /// * the en passant suffix `e.p.`.
+    ///
+    /// Check and checkmate suffixes are ignored. A leading lowercase `b` is
+    /// read as a pawn on the b-file. Use [`parse_lenient()`] to also consider
+    /// bishop moves.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidSan`] if `san` is not syntactically valid, even
+    /// with these relaxations.
+    ///
+    /// [`parse_lenient()`]: fn.parse_lenient.html
+    /// [`InvalidSan`]: struct.InvalidSan.html
+    pub fn from_bytes_lenient(san: &[u8]) -> Result<San, InvalidSan> {
+        let leading = san.iter().take_while(|ch| ch.is_ascii_whitespace()).count();
+        let mut san = trim_annotations(&san[leading..]);
+        if san.ends_with(b"e.p.") {
+            san = trim_annotations(&san[..(san.len() - 4)]);
+        }
+
+        match san {
+            b"--" => return Ok(San::Null),
+            b"0-0" | b"o-o" => return Ok(San::Castle(CastlingSide::KingSide)),
+            b"0-0-0" | b"o-o-o" => return Ok(San::Castle(CastlingSide::QueenSide)),
+            _ => (),
+        }
+
+        let mut normalized = san.to_vec();
+
+        if let Some(first) = normalized.first_mut() {
+            if b"nrqk".contains(first) {
+                first.make_ascii_uppercase();
+            }
+        }
+
+        let len = normalized.len();
+        let mut inserted = None;
+        if len >= 2 && normalized[len - 2] != b'=' {
+            let last = normalized[len - 1];
+            if b"NBRQKnbrqk".contains(&last) &&
+               (normalized[len - 2] == b'1' || normalized[len - 2] == b'8') {
+                normalized.insert(len - 1, b'=');
+                inserted = Some(len - 1);
+            }
+        }
+        if let Some(last) = normalized.last_mut() {
+            if b"nbrqk".contains(last) && len >= 2 {
+                last.make_ascii_uppercase();
+            }
+        }
+
+        // Report offsets relative to the original input.
+        let parsed = San::from_bytes(&normalized).map_err(|err| InvalidSan {
+            offset: leading + match inserted {
+                Some(index) if err.offset > index => err.offset - 1,
+                _ => err.offset,
+            }
+        })?;
+
+        match parsed {
+            San::Normal { role: Role::Pawn, file: Some(file), rank, capture: false, to, promotion } if file != to.file() =>
+                Ok(San::Normal { role: Role::Pawn, file: Some(file), rank, capture: true, to, promotion }),
+            san => Ok(san),
+        }
+    }
+
     /// Tries to convert the `San` to a legal move in the context of a
     /// position.
     ///
@@ -286,24 +399,57 @@ impl San {
     ///
     /// [`SanError`]: enum.SanError.html
     pub fn to_move<P: Position>(&self, pos: &P) -> Result<Move, SanError> {
+        self.resolve(pos, true)
+    }
+
+    /// Like [`to_move()`](#method.to_move), but ignores a missing or wrong
+    /// capture indicator for pieces other than pawns. Pawn moves are still
+    /// told apart by the file they are coming from.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SanError`] if there is no matching legal move.
+    ///
+    /// [`SanError`]: enum.SanError.html
+    pub fn to_move_lenient<P: Position>(&self, pos: &P) -> Result<Move, SanError> {
+        self.resolve(pos, false)
+    }
+
+    fn resolve<P: Position>(&self, pos: &P, strict: bool) -> Result<Move, SanError> {
         let mut legals = MoveList::new();
 
         match *self {
             San::Normal { role, file, rank, capture, to, promotion } => {
                 pos.san_candidates(role, to, &mut legals);
                 legals.retain(|m| match *m {
-                    Move::Normal { from, capture: c, promotion: p, .. } =>
+                    Move::Normal { from, promotion: p, .. } =>
                         file.map_or(true, |f| f == from.file()) &&
                         rank.map_or(true, |r| r == from.rank()) &&
-                        capture == c.is_some() &&
                         promotion == p,
                     Move::EnPassant { from, .. } =>
                         file.map_or(true, |f| f == from.file()) &&
                         rank.map_or(true, |r| r == from.rank()) &&
-                        capture &&
                         promotion.is_none(),
                     _ => false,
                 });
+
+                let capture_matches = |m: &Move| {
+                    capture == m.capture().is_some() || (!strict && role != Role::Pawn)
+                };
+
+                let capture_mismatch: Vec<Move> = legals.iter().filter(|m| !capture_matches(m)).cloned().collect();
+                legals.retain(|m| capture_matches(m));
+
+                if legals.is_empty() {
+                    return Err(SanError::IllegalSan {
+                        pinned: if capture_mismatch.is_empty() {
+                            pinned_candidates(pos, role, file, rank, capture, to)
+                        } else {
+                            Bitboard(0)
+                        },
+                        capture_mismatch,
+                    });
+                }
             },
             San::Castle(side) => pos.castling_moves(side, &mut legals),
             San::Put { role, to } => {
@@ -313,19 +459,66 @@ impl San {
                     _ => false,
                 });
             },
-            San::Null => return Err(SanError::IllegalSan),
+            San::Null => return Err(SanError::illegal()),
         }
 
-        legals.split_first().map_or(Err(SanError::IllegalSan), |(m, others)| {
+        legals.split_first().map_or(Err(SanError::illegal()), |(m, others)| {
             if others.is_empty() {
                 Ok(m.clone())
             } else {
-                Err(SanError::AmbiguousSan)
+                Err(SanError::AmbiguousSan { candidates: legals.to_vec() })
             }
         })
     }
 }
 
+/// Parses a square at `offset`.
+fn square_at(san: &[u8], offset: usize) -> Result<Square, InvalidSan> {
+    let file = san.get(offset).cloned().and_then(file_from_char).ok_or(InvalidSan { offset })?;
+    let rank = san.get(offset + 1).cloned().and_then(rank_from_char).ok_or(InvalidSan { offset: offset + 1 })?;
+    // Safe, because coordinates are validated by file_from_char and
+    // rank_from_char.
+    Ok(unsafe { Square::from_coords_unchecked(file, rank) })
+}
+
+/// Finds pieces that match a SAN and could reach the target square, if they
+/// were not pinned to their king.
+fn pinned_candidates<P: Position>(pos: &P, role: Role, file: Option<i8>, rank: Option<i8>,
+                                  capture: bool, to: Square) -> Bitboard {
+    let board = pos.board();
+    let turn = pos.turn();
+
+    let king = match board.king_of(turn) {
+        Some(king) => king,
+        None => return Bitboard(0),
+    };
+
+    let mut pinned = Bitboard(0);
+
+    for from in slider_blockers(board, pos.them(), king) & board.by_piece(role.of(turn)) {
+        if file.map_or(false, |f| f != from.file()) ||
+           rank.map_or(false, |r| r != from.rank()) ||
+           attacks::aligned(from, to, king) {
+            continue;
+        }
+
+        let reachable = if role != Role::Pawn {
+            attacks::attacks(from, role.of(turn), board.occupied()).contains(to)
+        } else if capture {
+            attacks::pawn_attacks(turn, from).contains(to)
+        } else {
+            let distance = turn.fold(1, -1) * (to.rank() - from.rank());
+            from.file() == to.file() && !board.occupied().contains(to) &&
+            (distance == 1 || (distance == 2 && from.rank() == turn.fold(1, 6)))
+        };
+
+        if reachable {
+            pinned.add(from);
+        }
+    }
+
+    pinned
+}
 
 impl FromStr for San {
     type Err = InvalidSan;
@@ -337,32 +530,254 @@ impl FromStr for San {
 
 impl fmt::Display for San {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            San::Normal { role, file, rank, capture, to, promotion } => {
-                if role != Role::Pawn {
-                    write!(f, "{}", (32 ^ role.char() as u8) as char)?;
-                }
-                if let Some(file) = file {
-                    write!(f, "{}", (b'a' + file as u8) as char)?;
-                }
-                if let Some(rank) = rank {
-                    write!(f, "{}", (b'1' + rank as u8) as char)?;
-                }
-                if capture {
-                    write!(f, "x")?;
-                }
-                write!(f, "{}", to)?;
-                if let Some(promotion) = promotion {
-                    write!(f, "={}", (32 ^ promotion.char() as u8) as char)?;
-                }
-                Ok(())
-            },
-            San::Castle(CastlingSide::KingSide) => write!(f, "O-O"),
-            San::Castle(CastlingSide::QueenSide) => write!(f, "O-O-O"),
-            San::Put { role: Role::Pawn, to } => write!(f, "@{}", to),
-            San::Put { role, to } => write!(f, "{}@{}", (32 ^ role.char() as u8) as char, to),
-            San::Null => write!(f, "--"),
+        write_san(f, self, &SanOpts::new())
+    }
+}
+
+fn write_san<W: fmt::Write>(f: &mut W, san: &San, opts: &SanOpts) -> fmt::Result {
+    match *san {
+        San::Normal { role, file, rank, capture, to, promotion } => {
+            if role != Role::Pawn {
+                f.write_str(opts.piece(role))?;
+            }
+            if let Some(file) = file {
+                f.write_char((b'a' + file as u8) as char)?;
+            }
+            if let Some(rank) = rank {
+                f.write_char((b'1' + rank as u8) as char)?;
+            }
+            if capture {
+                f.write_str(opts.capture)?;
+            }
+            write!(f, "{}", to)?;
+            if let Some(promotion) = promotion {
+                write!(f, "={}", opts.piece(promotion))?;
+            }
+            Ok(())
+        },
+        San::Castle(CastlingSide::KingSide) => f.write_str(opts.castle(CastlingSide::KingSide)),
+        San::Castle(CastlingSide::QueenSide) => f.write_str(opts.castle(CastlingSide::QueenSide)),
+        San::Put { role: Role::Pawn, to } => write!(f, "@{}", to),
+        San::Put { role, to } => write!(f, "{}@{}", opts.piece(role), to),
+        San::Null => f.write_str("--"),
+    }
+}
+
+/// Languages for piece symbols in SAN.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Locale {
+    /// `N`, `B`, `R`, `Q`, `K`
+    English,
+    /// `S`, `L`, `T`, `D`, `K`
+    German,
+    /// `C`, `F`, `T`, `D`, `R`
+    French,
+    /// `C`, `A`, `T`, `D`, `R`
+    Spanish,
+    /// `C`, `A`, `T`, `D`, `R`
+    Italian,
+    /// `C`, `B`, `T`, `D`, `R`
+    Portuguese,
+    /// `P`, `L`, `T`, `D`, `K`
+    Dutch,
+    /// `S`, `L`, `T`, `D`, `K`
+    Swedish,
+    /// `S`, `G`, `W`, `H`, `K`
+    Polish,
+    /// `J`, `S`, `V`, `D`, `K`
+    Czech,
+    /// `К`, `С`, `Л`, `Ф`, `Кр`
+    Russian,
+    /// `♘`, `♗`, `♖`, `♕`, `♔`
+    Figurine,
+}
+
+impl Locale {
+    /// Symbols for knight, bishop, rook, queen and king.
+    fn pieces(self) -> [&'static str; 5] {
+        match self {
+            Locale::English => ["N", "B", "R", "Q", "K"],
+            Locale::German | Locale::Swedish => ["S", "L", "T", "D", "K"],
+            Locale::French => ["C", "F", "T", "D", "R"],
+            Locale::Spanish | Locale::Italian => ["C", "A", "T", "D", "R"],
+            Locale::Portuguese => ["C", "B", "T", "D", "R"],
+            Locale::Dutch => ["P", "L", "T", "D", "K"],
+            Locale::Polish => ["S", "G", "W", "H", "K"],
+            Locale::Czech => ["J", "S", "V", "D", "K"],
+            Locale::Russian => ["К", "С", "Л", "Ф", "Кр"],
+            Locale::Figurine => ["♘", "♗", "♖", "♕", "♔"],
+        }
+    }
+}
+
+/// Options to read and write localized SAN.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// #
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use shakmaty::san::{San, SanOpts, Locale};
+///
+/// let mut german = SanOpts::new();
+/// german.locale(Locale::German).capture(":").zeros(true);
+///
+/// let san: San = "Nxf3".parse()?;
+/// assert_eq!(german.format(&san), "S:f3");
+/// assert_eq!(german.parse("S:f3")?, san);
+///
+/// let castle: San = "O-O-O".parse()?;
+/// assert_eq!(german.format(&castle), "0-0-0");
+///
+/// let figurine = SanOpts::new().locale(Locale::Figurine).format(&san);
+/// assert_eq!(figurine, "♘xf3");
+/// #
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SanOpts {
+    locale: Locale,
+    capture: &'static str,
+    zeros: bool,
+}
+
+impl SanOpts {
+    /// Standard English SAN, e.g. `Nxf3` and `O-O`.
+    pub fn new() -> SanOpts {
+        SanOpts {
+            locale: Locale::English,
+            capture: "x",
+            zeros: false,
+        }
+    }
+
+    /// Set the language of piece symbols.
+    pub fn locale(&mut self, locale: Locale) -> &mut SanOpts {
+        self.locale = locale;
+        self
+    }
+
+    /// Set the capture symbol, e.g. `x` or `:`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capture` is empty.
+    pub fn capture(&mut self, capture: &'static str) -> &mut SanOpts {
+        assert!(!capture.is_empty());
+        self.capture = capture;
+        self
+    }
+
+    /// Decide if castling should be written with zeros (`0-0`) instead of
+    /// letters (`O-O`). Both are accepted when parsing.
+    pub fn zeros(&mut self, zeros: bool) -> &mut SanOpts {
+        self.zeros = zeros;
+        self
+    }
+
+    fn piece(&self, role: Role) -> &'static str {
+        match role {
+            Role::Pawn => "",
+            Role::Knight => self.locale.pieces()[0],
+            Role::Bishop => self.locale.pieces()[1],
+            Role::Rook => self.locale.pieces()[2],
+            Role::Queen => self.locale.pieces()[3],
+            Role::King => self.locale.pieces()[4],
+        }
+    }
+
+    fn castle(&self, side: CastlingSide) -> &'static str {
+        match (side, self.zeros) {
+            (CastlingSide::KingSide, false) => "O-O",
+            (CastlingSide::KingSide, true) => "0-0",
+            (CastlingSide::QueenSide, false) => "O-O-O",
+            (CastlingSide::QueenSide, true) => "0-0-0",
+        }
+    }
+
+    /// Writes a SAN with these options.
+    pub fn format(&self, san: &San) -> String {
+        let mut result = String::new();
+        write_san(&mut result, san, self).expect("write to string");
+        result
+    }
+
+    /// Writes a SAN with check or checkmate suffix with these options.
+    pub fn format_plus(&self, san: &SanPlus) -> String {
+        let mut result = self.format(&san.san);
+        if san.checkmate {
+            result.push('#');
+        } else if san.check {
+            result.push('+');
+        }
+        result
+    }
+
+    /// Translates localized SAN to standard English SAN.
+    fn translate(&self, san: &str) -> String {
+        if san.starts_with("0-0") {
+            return san.replacen("0-0", "O-O", 1).replacen("-0", "-O", 1);
         }
+
+        // Try longer symbols first, e.g. `Кр` before `К`.
+        let mut symbols: Vec<(&str, char)> = self.locale.pieces().iter().cloned()
+            .zip("NBRQK".chars())
+            .collect();
+        symbols.push((self.capture, 'x'));
+        symbols.sort_by_key(|&(symbol, _)| !symbol.len());
+
+        let mut result = String::with_capacity(san.len());
+        let mut rest = san;
+        while let Some(ch) = rest.chars().next() {
+            match symbols.iter().find(|&&(symbol, _)| rest.starts_with(symbol)) {
+                Some(&(symbol, english)) => {
+                    result.push(english);
+                    rest = &rest[symbol.len()..];
+                },
+                None => {
+                    result.push(ch);
+                    rest = &rest[ch.len_utf8()..];
+                },
+            }
+        }
+        result
+    }
+
+    /// Parses a SAN written with these options. Ignores a possible check or
+    /// checkmate suffix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidSan`] if `san` is not syntactically valid. The
+    /// offset refers to the SAN after translation to English.
+    ///
+    /// [`InvalidSan`]: struct.InvalidSan.html
+    pub fn parse(&self, san: &str) -> Result<San, InvalidSan> {
+        San::from_bytes(self.translate(san).as_bytes())
+    }
+
+    /// Parses a SAN written with these options, including a possible check
+    /// or checkmate suffix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidSan`] if `san` is not syntactically valid.
+    ///
+    /// [`InvalidSan`]: struct.InvalidSan.html
+    pub fn parse_plus(&self, san: &str) -> Result<SanPlus, InvalidSan> {
+        SanPlus::from_bytes(self.translate(san).as_bytes())
+    }
+}
+
+impl Default for SanOpts {
+    fn default() -> SanOpts {
+        SanOpts::new()
     }
 }
 
@@ -415,6 +830,62 @@ impl fmt::Display for SanPlus {
     }
 }
 
+/// Strips trailing check, checkmate and annotation symbols like `+`, `!?`
+/// or `+/-`, and trailing whitespace.
+fn trim_annotations(mut san: &[u8]) -> &[u8] {
+    loop {
+        match san.split_last() {
+            Some((&last, rest)) if b"!?+#=/-".contains(&last) || last.is_ascii_whitespace() => {
+                if rest.is_empty() || rest == b"-" {
+                    // Keep the null move `--`.
+                    return san;
+                }
+                san = rest;
+            },
+            _ => return san,
+        }
+    }
+}
+
+/// Parses and resolves a SAN in the context of a position, accepting the
+/// variations described in [`San::from_bytes_lenient()`]. A leading
+/// lowercase `b` is tried as a pawn move first, and then as a bishop move.
+///
+/// # Errors
+///
+/// Returns [`SanError`] if `san` is not syntactically valid, or there is not
+/// exactly one matching legal move.
+///
+/// # Examples
+///
+/// ```
+/// use shakmaty::{Chess, Square};
+/// use shakmaty::san;
+///
+/// let pos = Chess::default();
+/// let m = san::parse_lenient(&pos, b"nf3!?").expect("legal");
+/// assert_eq!(m.to(), Square::F3);
+/// ```
+///
+/// [`San::from_bytes_lenient()`]: enum.San.html#method.from_bytes_lenient
+/// [`SanError`]: enum.SanError.html
+pub fn parse_lenient<P: Position>(pos: &P, san: &[u8]) -> Result<Move, SanError> {
+    let result = San::from_bytes_lenient(san)
+        .map_err(SanError::InvalidSan)
+        .and_then(|san| san.to_move_lenient(pos));
+
+    match (result, san.first()) {
+        (Err(_), Some(&b'b')) => {
+            let mut bishop = san.to_vec();
+            bishop[0] = b'B';
+            San::from_bytes_lenient(&bishop)
+                .map_err(SanError::InvalidSan)
+                .and_then(|san| san.to_move_lenient(pos))
+        },
+        (result, _) => result,
+    }
+}
+
 /// Converts a move to Standard Algebraic Notation including possible
 /// check and checkmate suffixes.
 pub fn san_plus<P: Position>(mut pos: P, m: &Move) -> SanPlus {
@@ -493,6 +964,136 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_locales() {
+        let locales = [Locale::English, Locale::German, Locale::French, Locale::Spanish,
+                       Locale::Italian, Locale::Portuguese, Locale::Dutch, Locale::Swedish,
+                       Locale::Polish, Locale::Czech, Locale::Russian, Locale::Figurine];
+
+        for &locale in &locales {
+            for &zeros in &[false, true] {
+                let mut opts = SanOpts::new();
+                opts.locale(locale).zeros(zeros);
+                for san in &["e4", "bxa8=R+", "Nf3", "Bba5", "N2c4", "Qh1=K", "Kxe2",
+                             "K@b3", "@e4#", "--", "O-O", "O-O-O+", "Rexd8#"] {
+                    let san: SanPlus = san.parse().expect("valid san");
+                    let localized = opts.format_plus(&san);
+                    assert_eq!(opts.parse_plus(&localized).expect("valid localized san"), san,
+                               "{:?} {}", locale, localized);
+                }
+            }
+        }
+
+        let mut russian = SanOpts::new();
+        russian.locale(Locale::Russian).capture(":");
+        assert_eq!(russian.format(&"Kxe2".parse().expect("valid san")), "Кр:e2");
+        assert_eq!(russian.format(&"Nxe2".parse().expect("valid san")), "К:e2");
+        assert_eq!(russian.parse("Кр:e2").expect("valid san").to_string(), "Kxe2");
+
+        let mut german = SanOpts::new();
+        german.locale(Locale::German);
+        assert_eq!(german.parse("0-0").expect("valid san"), San::Castle(CastlingSide::KingSide));
+        assert_eq!(german.parse("Lb5").expect("valid san").to_string(), "Bb5");
+        assert_eq!(german.format(&"b8=Q".parse().expect("valid san")), "b8=D");
+    }
+
+    #[test]
+    fn test_lenient() {
+        for &(lenient, san) in &[("nf3", "Nf3"), ("0-0", "O-O"), ("o-o-o", "O-O-O"),
+                                 ("ed5", "exd5"), ("e8Q", "e8=Q"), ("e8q+", "e8=Q"),
+                                 ("axb8n", "axb8=N"), ("Nf3!?", "Nf3"), ("e4 +/-", "e4"),
+                                 ("exd6 e.p.", "exd6"), ("exd6e.p.+", "exd6"),
+                                 ("Qxh7#??", "Qxh7"), ("--", "--"), ("b4", "b4")] {
+            let result = San::from_bytes_lenient(lenient.as_bytes()).expect("valid lenient san");
+            assert_eq!(result.to_string(), san, "lenient {}", lenient);
+        }
+
+        for lenient in &["", "!?", "e9", "x", "Nf3 Nf6"] {
+            assert!(San::from_bytes_lenient(lenient.as_bytes()).is_err(), "{} should be invalid", lenient);
+        }
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        use fen::Fen;
+        use position::Chess;
+
+        let fen: Fen = "r1bqkbnr/pppp1ppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        let m = parse_lenient(&pos, b"a6").expect("legal");
+        assert_eq!(m.to(), Square::A6);
+        assert_eq!(parse_lenient(&pos, b"ng8f6").expect("legal").from(), Some(Square::G8));
+        assert_eq!(parse_lenient(&pos, b"Nd4!").expect("legal").from(), Some(Square::C6));
+        assert_eq!(parse_lenient(&pos, b"Nb4").expect("legal").from(), Some(Square::C6));
+        assert_eq!(parse_lenient(&pos, b"bb4").expect("legal").from(), Some(Square::F8));
+        assert_eq!(parse_lenient(&pos, b"Bxb4 ?!").expect("legal").capture(), None);
+        assert!(match parse_lenient(&pos, b"Ne7") {
+            Err(SanError::AmbiguousSan { .. }) => true,
+            _ => false,
+        });
+        assert_eq!(parse_lenient(&pos, b"nge7").expect("legal").from(), Some(Square::G8));
+        assert!(parse_lenient(&pos, b"Nf3").is_err());
+
+        // The capture indicator is still significant for pawns.
+        let fen: Fen = "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+        assert_eq!(parse_lenient(&pos, b"e6").expect("legal").from(), Some(Square::E5));
+        assert_eq!(parse_lenient(&pos, b"ed6 e.p.").expect("legal"),
+                   Move::EnPassant { from: Square::E5, to: Square::D6 });
+    }
+
+    #[test]
+    fn test_invalid_offset() {
+        for &(san, offset) in &[("", 0), ("X", 0), ("e", 1), ("Nf", 2), ("e9", 1), ("Nxz3", 2),
+                                ("e8=", 3), ("e8=X", 3), ("e4!", 2), ("Ra1a9", 4), ("@e9", 2)] {
+            assert_eq!(San::from_bytes(san.as_bytes()).map_err(|err| err.offset()),
+                       Err(offset), "{}", san);
+        }
+
+        assert_eq!(San::from_bytes_lenient(b"  e9!?").map_err(|err| err.offset()), Err(3));
+        assert_eq!(San::from_bytes_lenient(b"e8X").map_err(|err| err.offset()), Err(2));
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        use fen::Fen;
+        use position::Chess;
+
+        let fen: Fen = "4k3/8/8/b7/8/2N2N2/8/1N2K2R w K - 0 1".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        let err = "Nd2".parse::<San>().expect("valid san").to_move(&pos).expect_err("ambiguous");
+        assert_eq!(err, SanError::AmbiguousSan {
+            candidates: vec![
+                Move::Normal { role: Role::Knight, from: Square::B1, capture: None, to: Square::D2, promotion: None },
+                Move::Normal { role: Role::Knight, from: Square::F3, capture: None, to: Square::D2, promotion: None },
+            ],
+        });
+        assert_eq!(err.to_string(), "ambiguous san (candidates: Nb1-d2 Nf3-d2)");
+
+        let err = "Ne4".parse::<San>().expect("valid san").to_move(&pos).expect_err("pinned");
+        assert_eq!(err, SanError::IllegalSan {
+            pinned: Bitboard::from_square(Square::C3),
+            capture_mismatch: Vec::new(),
+        });
+        assert_eq!(err.to_string(), "illegal san (pinned piece on c3)");
+
+        let err = "Rxh8".parse::<San>().expect("valid san").to_move(&pos).expect_err("no capture");
+        assert_eq!(err, SanError::IllegalSan {
+            pinned: Bitboard(0),
+            capture_mismatch: vec![
+                Move::Normal { role: Role::Rook, from: Square::H1, capture: None, to: Square::H8, promotion: None },
+            ],
+        });
+
+        let err = "Qd1".parse::<San>().expect("valid san").to_move(&pos).expect_err("no queen");
+        assert_eq!(err, SanError::IllegalSan { pinned: Bitboard(0), capture_mismatch: Vec::new() });
+
+        assert_eq!(parse_lenient(&pos, b"Nc9").expect_err("invalid"),
+                   SanError::InvalidSan(InvalidSan { offset: 2 }));
+    }
+
     #[cfg(nightly)]
     #[bench]
     fn bench_parse_san_move_complicated(b: &mut Bencher) {
@@ -0,0 +1,288 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! String based `Serialize` and `Deserialize` implementations.
+//!
+//! A [`Move`](../enum.Move.html) can not be read back from a string without
+//! a position, so it has no implementations of its own. Convert it to a
+//! [`Uci`](../uci/enum.Uci.html) to serialize it as a string, or use
+//! `shakmaty::structured::moves` for the structured form.
+
+use std::fmt;
+use std::str::FromStr;
+use std::marker::PhantomData;
+
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use serde::de::{self, Visitor};
+
+use square::Square;
+use types::{Color, Role, Piece, Pocket, Pockets, RemainingChecks};
+use bitboard::Bitboard;
+use board::Board;
+use setup::CastlingSide;
+use position::Chess;
+use fen;
+use fen::{Fen, FenOpts};
+use uci::Uci;
+use san::{San, SanPlus};
+
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    _marker: PhantomData<T>,
+}
+
+impl<'de, T> Visitor<'de> for FromStrVisitor<T>
+    where T: FromStr,
+          T::Err: fmt::Display
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<T, E> {
+        s.parse().map_err(|err| E::custom(format!("{}: {:?}", err, s)))
+    }
+}
+
+fn deserialize_from_str<'de, D, T>(deserializer: D, expecting: &'static str) -> Result<T, D::Error>
+    where D: Deserializer<'de>,
+          T: FromStr,
+          T::Err: fmt::Display
+{
+    deserializer.deserialize_str(FromStrVisitor { expecting, _marker: PhantomData })
+}
+
+macro_rules! string_serde {
+    ($t:ty, $expecting:expr) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                deserialize_from_str(deserializer, $expecting)
+            }
+        }
+    }
+}
+
+macro_rules! name_serde {
+    ($t:ident, $expecting:expr, $($variant:ident => $name:expr),+) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(match *self {
+                    $($t::$variant => $name),+
+                })
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                struct NameVisitor;
+
+                impl<'de> Visitor<'de> for NameVisitor {
+                    type Value = $t;
+
+                    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                        f.write_str($expecting)
+                    }
+
+                    fn visit_str<E: de::Error>(self, s: &str) -> Result<$t, E> {
+                        match s {
+                            $($name => Ok($t::$variant),)+
+                            _ => Err(E::unknown_variant(s, &[$($name),+])),
+                        }
+                    }
+                }
+
+                deserializer.deserialize_str(NameVisitor)
+            }
+        }
+    }
+}
+
+macro_rules! remote_serde {
+    ($t:ident, $def:ident) => {
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $def::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<$t, D::Error> {
+                $def::deserialize(deserializer)
+            }
+        }
+    }
+}
+
+name_serde!(Color, "white or black",
+            White => "white", Black => "black");
+name_serde!(Role, "a role like pawn or knight",
+            Pawn => "pawn", Knight => "knight", Bishop => "bishop",
+            Rook => "rook", Queen => "queen", King => "king");
+name_serde!(CastlingSide, "kingside or queenside",
+            KingSide => "kingside", QueenSide => "queenside");
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Pocket")]
+struct PocketDef {
+    pawns: u8,
+    knights: u8,
+    bishops: u8,
+    rooks: u8,
+    queens: u8,
+    kings: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Pockets")]
+struct PocketsDef {
+    white: Pocket,
+    black: Pocket,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "RemainingChecks")]
+struct RemainingChecksDef {
+    white: u8,
+    black: u8,
+}
+
+remote_serde!(Pocket, PocketDef);
+remote_serde!(Pockets, PocketsDef);
+remote_serde!(RemainingChecks, RemainingChecksDef);
+
+impl Serialize for Bitboard {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bitboard {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Bitboard, D::Error> {
+        u64::deserialize(deserializer).map(Bitboard)
+    }
+}
+
+string_serde!(Square, "a square name like e4");
+string_serde!(Board, "a board fen");
+string_serde!(Fen, "a fen");
+string_serde!(Uci, "a uci move");
+string_serde!(San, "a san move");
+string_serde!(SanPlus, "a san move");
+
+impl Serialize for Piece {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_char(self.char())
+    }
+}
+
+impl<'de> Deserialize<'de> for Piece {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Piece, D::Error> {
+        struct PieceVisitor;
+
+        impl<'de> Visitor<'de> for PieceVisitor {
+            type Value = Piece;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a piece character like N or p")
+            }
+
+            fn visit_char<E: de::Error>(self, ch: char) -> Result<Piece, E> {
+                Piece::from_char(ch).ok_or_else(|| E::custom(format!("invalid piece: {:?}", ch)))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Piece, E> {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => self.visit_char(ch),
+                    _ => Err(E::custom(format!("invalid piece: {:?}", s))),
+                }
+            }
+        }
+
+        deserializer.deserialize_char(PieceVisitor)
+    }
+}
+
+impl Serialize for Chess {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&fen::fen(self, &FenOpts::default()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Chess {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Chess, D::Error> {
+        let fen = Fen::deserialize(deserializer)?;
+        fen.position().map_err(|err| de::Error::custom(format!("illegal position: {:?}", err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+    use setup::Setup;
+
+    fn roundtrip<T>(value: &T, json: &str)
+        where T: Serialize + for<'de> Deserialize<'de> + PartialEq + fmt::Debug
+    {
+        assert_eq!(serde_json::to_string(value).expect("serialize"), json);
+        assert_eq!(&serde_json::from_str::<T>(json).expect("deserialize"), value);
+    }
+
+    #[test]
+    fn test_strings() {
+        roundtrip(&Square::E4, "\"e4\"");
+        roundtrip(&Color::White, "\"white\"");
+        roundtrip(&Role::Knight, "\"knight\"");
+        roundtrip(&Color::Black.queen(), "\"q\"");
+        roundtrip(&CastlingSide::QueenSide, "\"queenside\"");
+        roundtrip(&Bitboard(0x81), "129");
+        roundtrip(&Board::new(), "\"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR\"");
+        roundtrip(&Uci::from_str("e7e8q").unwrap(), "\"e7e8q\"");
+        roundtrip(&SanPlus::from_str("Nbd7+").unwrap(), "\"Nbd7+\"");
+
+        let fen: Fen = "4k3/8/8/8/8/8/8/4K2R w K - 0 1".parse().unwrap();
+        roundtrip(&fen, "\"4k3/8/8/8/8/8/8/4K2R w K - 0 1\"");
+    }
+
+    #[test]
+    fn test_structs() {
+        roundtrip(&RemainingChecks { white: 1, black: 2 }, r#"{"white":1,"black":2}"#);
+
+        let mut pockets = Pockets::default();
+        pockets.add(Color::White.knight());
+        assert_eq!(serde_json::from_str::<Pockets>(&serde_json::to_string(&pockets).unwrap()).unwrap(), pockets);
+    }
+
+    #[test]
+    fn test_position() {
+        let pos = Chess::default();
+        let json = serde_json::to_string(&pos).unwrap();
+        assert_eq!(json, "\"rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\"");
+        assert_eq!(serde_json::from_str::<Chess>(&json).unwrap().board(), pos.board());
+
+        assert!(serde_json::from_str::<Chess>("\"8/8/8/8/8/8/8/8 w - - 0 1\"").is_err());
+        assert!(serde_json::from_str::<Square>("\"i9\"").is_err());
+    }
+}
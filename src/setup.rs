@@ -19,6 +19,7 @@ use bitboard::Bitboard;
 use attacks;
 use types::{Color, Role, Pockets, RemainingChecks};
 use board::Board;
+use fen::Fen;
 
 use option_filter::OptionFilterExt;
 
@@ -48,6 +49,32 @@ pub trait Setup {
     fn their(&self, role: Role) -> Bitboard {
         self.them() & self.board().by_role(role)
     }
+
+    /// The color-symmetric setup: the board is flipped vertically with
+    /// colors swapped, the other side is to move, and castling rights, en
+    /// passant square, pockets and remaining checks are mirrored accordingly.
+    fn mirror(&self) -> Fen {
+        let mut board = self.board().clone();
+        board.flip_vertical();
+        board.swap_colors();
+
+        Fen {
+            board,
+            pockets: self.pockets().map(|p| Pockets {
+                white: p.black.clone(),
+                black: p.white.clone(),
+            }),
+            turn: !self.turn(),
+            castling_rights: self.castling_rights().flip_vertical(),
+            ep_square: self.ep_square().map(Square::flip_vertical),
+            remaining_checks: self.remaining_checks().map(|r| RemainingChecks {
+                white: r.black,
+                black: r.white,
+            }),
+            halfmove_clock: self.halfmove_clock(),
+            fullmoves: self.fullmoves(),
+        }
+    }
 }
 
 /// `KingSide` (O-O) or `QueenSide` (O-O-O).
@@ -73,6 +100,30 @@ impl CastlingSide {
     }
 }
 
+/// `Standard` or `Chess960` notation for castling moves.
+///
+/// In standard notation castling is written as a king move two squares
+/// towards the rook, e.g. `e1g1`. In Chess960 notation castling is written
+/// as the king capturing its own rook, e.g. `e1h1`. This corresponds to the
+/// `UCI_Chess960` option of UCI engines.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CastlingMode {
+    Standard,
+    Chess960,
+}
+
+impl CastlingMode {
+    /// `Chess960` if `chess960` is set, `Standard` otherwise.
+    pub fn from_chess960(chess960: bool) -> CastlingMode {
+        if chess960 { CastlingMode::Chess960 } else { CastlingMode::Standard }
+    }
+
+    /// Tests if castling moves are written as the king capturing its rook.
+    pub fn is_chess960(self) -> bool {
+        self == CastlingMode::Chess960
+    }
+}
+
 pub struct SwapTurn<S: Setup>(pub S);
 
 impl<S: Setup> Setup for SwapTurn<S> {
@@ -162,6 +213,21 @@ impl Castling {
         }
     }
 
+    /// Tests if a side has castling rights for at least two rooks, but the
+    /// king is not strictly between the outermost of them.
+    pub fn king_not_between_rooks(setup: &Setup) -> bool {
+        [Color::Black, Color::White].iter().any(|&color| {
+            let rooks = setup.castling_rights() & setup.board().by_piece(color.rook()) &
+                        Bitboard::relative_rank(color, 0);
+
+            rooks.more_than_one() && match (setup.board().king_of(color), rooks.first(), rooks.last()) {
+                (Some(king), Some(a_side), Some(h_side)) =>
+                    king.rank() != a_side.rank() || !(a_side < king && king < h_side),
+                _ => false,
+            }
+        })
+    }
+
     pub fn discard_rook(&mut self, square: Square) {
         self.rook[0] = self.rook[0].filter(|sq| *sq != square);
         self.rook[1] = self.rook[1].filter(|sq| *sq != square);
@@ -202,4 +268,15 @@ mod tests {
     use super::*;
 
     struct _AssertObjectSafe(Box<Setup>);
+
+    #[test]
+    fn test_mirror() {
+        let fen: Fen = "r3k3/8/8/3pP3/8/8/8/4K2R w Kq d6 0 3".parse().expect("valid fen");
+        let mirrored = fen.mirror();
+        assert_eq!(mirrored.to_string(), "4k2r/8/8/8/3Pp3/8/8/R3K3 b Qk d3 0 3");
+        assert_eq!(mirrored.mirror(), fen);
+
+        let fen: Fen = "4k3/8/8/8/8/8/8/4K3[Qn] w - - 1+2 0 1".parse().expect("valid fen");
+        assert_eq!(fen.mirror().to_string(), "4k3/8/8/8/8/8/8/4K3[Nq] b - - 2+1 0 1");
+    }
 }
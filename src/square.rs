@@ -224,6 +224,66 @@ impl Square {
     pub fn combine(self, rank: Square) -> Square {
         unsafe { Square::from_index_unchecked(self.file() | (rank.rank() << 3)) }
     }
+
+    /// Flip the square vertically, i.e. mirror the rank.
+    ///
+    /// ```
+    /// use shakmaty::Square;
+    ///
+    /// assert_eq!(Square::B3.flip_vertical(), Square::B6);
+    /// ```
+    #[inline]
+    pub fn flip_vertical(self) -> Square {
+        unsafe { Square::from_index_unchecked(self as i8 ^ 0x38) }
+    }
+
+    /// Flip the square horizontally, i.e. mirror the file.
+    ///
+    /// ```
+    /// use shakmaty::Square;
+    ///
+    /// assert_eq!(Square::B3.flip_horizontal(), Square::G3);
+    /// ```
+    #[inline]
+    pub fn flip_horizontal(self) -> Square {
+        unsafe { Square::from_index_unchecked(self as i8 ^ 0x07) }
+    }
+
+    /// Flip at the a1-h8 diagonal, i.e. swap file and rank.
+    ///
+    /// ```
+    /// use shakmaty::Square;
+    ///
+    /// assert_eq!(Square::B3.flip_diagonal(), Square::C2);
+    /// ```
+    #[inline]
+    pub fn flip_diagonal(self) -> Square {
+        unsafe { Square::from_coords_unchecked(self.rank(), self.file()) }
+    }
+
+    /// Flip at the h1-a8 diagonal.
+    ///
+    /// ```
+    /// use shakmaty::Square;
+    ///
+    /// assert_eq!(Square::B3.flip_anti_diagonal(), Square::F7);
+    /// ```
+    #[inline]
+    pub fn flip_anti_diagonal(self) -> Square {
+        unsafe { Square::from_coords_unchecked(7 - self.rank(), 7 - self.file()) }
+    }
+
+    /// Rotate the square by 180 degrees.
+    ///
+    /// ```
+    /// use shakmaty::Square;
+    ///
+    /// assert_eq!(Square::B3.rotate_180(), Square::G6);
+    /// ```
+    #[inline]
+    pub fn rotate_180(self) -> Square {
+        unsafe { Square::from_index_unchecked(self as i8 ^ 0x3f) }
+    }
 }
 
 impl From<Square> for i8 {
@@ -0,0 +1,224 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Structured serde representations (requires the `serde` feature).
+//!
+//! By default [`Fen`], [`Uci`] and [`San`] serialize as their usual string
+//! notation. The modules in here can be used with `#[serde(with = "...")]`
+//! to serialize them field by field instead.
+//!
+//! A [`Move`] can only be read back from a string in the context of a
+//! position, so it has no default implementations. Store it as a [`Uci`]
+//! (see [`Uci::from_move()`]) to get the string form, or use [`moves`] for
+//! the structured form.
+//!
+//! # Example
+//!
+//! ```
+//! #[macro_use]
+//! extern crate serde;
+//! extern crate shakmaty;
+//!
+//! use shakmaty::Move;
+//! use shakmaty::fen::Fen;
+//! use shakmaty::uci::Uci;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "shakmaty::structured::fen")]
+//!     fen: Fen,
+//!     best_move: Uci,
+//!     #[serde(with = "shakmaty::structured::moves")]
+//!     played: Move,
+//! }
+//! # fn main() {}
+//! ```
+//!
+//! [`Fen`]: ../fen/struct.Fen.html
+//! [`Uci`]: ../uci/enum.Uci.html
+//! [`San`]: ../san/enum.San.html
+//! [`Move`]: ../enum.Move.html
+//! [`Uci::from_move()`]: ../uci/enum.Uci.html#method.from_move
+//! [`moves`]: moves/index.html
+
+/// Serialize a [`Fen`](../../fen/struct.Fen.html) as a struct.
+pub mod fen {
+    use serde::{Serializer, Deserializer};
+
+    use square::Square;
+    use types::{Color, Pockets, RemainingChecks};
+    use bitboard::Bitboard;
+    use board::Board;
+    use fen::Fen;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "Fen")]
+    struct FenDef {
+        board: Board,
+        pockets: Option<Pockets>,
+        turn: Color,
+        castling_rights: Bitboard,
+        ep_square: Option<Square>,
+        remaining_checks: Option<RemainingChecks>,
+        halfmove_clock: u32,
+        fullmoves: u32,
+    }
+
+    pub fn serialize<S: Serializer>(fen: &Fen, serializer: S) -> Result<S::Ok, S::Error> {
+        FenDef::serialize(fen, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Fen, D::Error> {
+        FenDef::deserialize(deserializer)
+    }
+}
+
+/// Serialize a [`Uci`](../../uci/enum.Uci.html) as an enum.
+pub mod uci {
+    use serde::{Serializer, Deserializer};
+
+    use square::Square;
+    use types::Role;
+    use uci::Uci;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "Uci")]
+    enum UciDef {
+        Normal {
+            from: Square,
+            to: Square,
+            promotion: Option<Role>,
+        },
+        Put { role: Role, to: Square },
+        Null,
+    }
+
+    pub fn serialize<S: Serializer>(uci: &Uci, serializer: S) -> Result<S::Ok, S::Error> {
+        UciDef::serialize(uci, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uci, D::Error> {
+        UciDef::deserialize(deserializer)
+    }
+}
+
+/// Serialize a [`Move`](../../enum.Move.html) as an enum.
+pub mod moves {
+    use serde::{Serializer, Deserializer};
+
+    use square::Square;
+    use types::{Role, Move};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "Move")]
+    enum MoveDef {
+        Normal {
+            role: Role,
+            from: Square,
+            capture: Option<Role>,
+            to: Square,
+            promotion: Option<Role>,
+        },
+        EnPassant { from: Square, to: Square },
+        Castle { king: Square, rook: Square },
+        Put { role: Role, to: Square },
+    }
+
+    pub fn serialize<S: Serializer>(m: &Move, serializer: S) -> Result<S::Ok, S::Error> {
+        MoveDef::serialize(m, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Move, D::Error> {
+        MoveDef::deserialize(deserializer)
+    }
+}
+
+/// Serialize a [`San`](../../san/enum.San.html) as an enum.
+pub mod san {
+    use serde::{Serializer, Deserializer};
+
+    use square::Square;
+    use types::Role;
+    use setup::CastlingSide;
+    use san::San;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "San")]
+    enum SanDef {
+        Normal {
+            role: Role,
+            file: Option<i8>,
+            rank: Option<i8>,
+            capture: bool,
+            to: Square,
+            promotion: Option<Role>,
+        },
+        Castle(CastlingSide),
+        Put { role: Role, to: Square },
+        Null,
+    }
+
+    pub fn serialize<S: Serializer>(san: &San, serializer: S) -> Result<S::Ok, S::Error> {
+        SanDef::serialize(san, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<San, D::Error> {
+        SanDef::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    use square::Square;
+    use types::{Role, Move};
+    use setup::CastlingMode;
+    use fen::Fen;
+    use uci::Uci;
+    use san::San;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Record {
+        #[serde(with = "::structured::fen")]
+        fen: Fen,
+        #[serde(with = "::structured::uci")]
+        uci: Uci,
+        #[serde(with = "::structured::san")]
+        san: San,
+        #[serde(with = "::structured::moves")]
+        played: Move,
+        best: Uci,
+    }
+
+    #[test]
+    fn test_structured() {
+        let record = Record {
+            fen: Fen::default(),
+            uci: "g1f3".parse().unwrap(),
+            san: "Nf3".parse().unwrap(),
+            played: Move::Put { role: Role::Pawn, to: Square::D5 },
+            best: Uci::from_move(&Move::Castle { king: Square::E1, rook: Square::H1 }, CastlingMode::Standard),
+        };
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains(r#""turn":"white""#));
+        assert!(json.contains(r#""uci":{"Normal":{"from":"g1","to":"f3","promotion":null}}"#));
+        assert!(json.contains(r#""played":{"Put":{"role":"pawn","to":"d5"}}"#));
+        assert!(json.contains(r#""best":"e1g1""#));
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
+}
@@ -0,0 +1,316 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Render boards as SVG diagrams (requires the `svg` feature).
+//!
+//! The diagrams are self-contained and do not depend on installed fonts.
+//! Pieces are drawn with the Cburnett piece set, defined once and referenced
+//! for each square.
+//!
+//! # Examples
+//!
+//! ```
+//! use shakmaty::{Chess, Color, Square};
+//! use shakmaty::svg::SvgOpts;
+//!
+//! let pos = Chess::default();
+//!
+//! let svg = SvgOpts::new()
+//!     .orientation(Color::Black)
+//!     .coordinates(true)
+//!     .arrow(Square::E2, Square::E4)
+//!     .position(&pos);
+//!
+//! assert!(svg.starts_with("<svg"));
+//! ```
+
+use square::Square;
+use types::{Color, Role, Move, ROLES};
+use bitboard::Bitboard;
+use board::Board;
+use position::Position;
+
+const LIGHT: &str = "#ffce9e";
+const DARK: &str = "#d18b47";
+const LAST_MOVE: &str = "#9bc700";
+const HIGHLIGHT: &str = "#15781b";
+const ARROW: &str = "#15781b";
+
+/// SVG rendering options.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SvgOpts {
+    size: u32,
+    orientation: Color,
+    coordinates: bool,
+    highlights: Bitboard,
+    last_move: Bitboard,
+    check: Option<Square>,
+    arrows: Vec<(Square, Square)>,
+}
+
+impl SvgOpts {
+    /// 45 pixels per square from White's point of view, without coordinates.
+    pub fn new() -> SvgOpts {
+        SvgOpts {
+            size: 45,
+            orientation: Color::White,
+            coordinates: false,
+            highlights: Bitboard(0),
+            last_move: Bitboard(0),
+            check: None,
+            arrows: Vec::new(),
+        }
+    }
+
+    /// Set the size of a square in pixels.
+    pub fn size(&mut self, size: u32) -> &mut SvgOpts {
+        self.size = size;
+        self
+    }
+
+    /// Show the board from the point of view of `orientation`.
+    pub fn orientation(&mut self, orientation: Color) -> &mut SvgOpts {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Decide if rank and file labels should be drawn around the board.
+    pub fn coordinates(&mut self, coordinates: bool) -> &mut SvgOpts {
+        self.coordinates = coordinates;
+        self
+    }
+
+    /// Highlight squares.
+    pub fn highlights(&mut self, highlights: Bitboard) -> &mut SvgOpts {
+        self.highlights = highlights;
+        self
+    }
+
+    /// Mark the origin and target squares of a move.
+    pub fn last_move(&mut self, m: &Move) -> &mut SvgOpts {
+        self.last_move = Bitboard::from_square(m.to());
+        if let Some(from) = m.from() {
+            self.last_move.add(from);
+        }
+        self
+    }
+
+    /// Mark a king in check. [`position()`](fn.position.html) does this
+    /// automatically.
+    pub fn check(&mut self, king: Option<Square>) -> &mut SvgOpts {
+        self.check = king;
+        self
+    }
+
+    /// Add an arrow from one square to another.
+    pub fn arrow(&mut self, from: Square, to: Square) -> &mut SvgOpts {
+        self.arrows.push((from, to));
+        self
+    }
+
+    /// See [`board()`](fn.board.html).
+    pub fn board(&self, board: &Board) -> String {
+        self::board(board, self)
+    }
+
+    /// See [`position()`](fn.position.html).
+    pub fn position<P: Position>(&self, pos: &P) -> String {
+        self::position(pos, self)
+    }
+
+    fn margin(&self) -> u32 {
+        if self.coordinates { self.size / 2 } else { 0 }
+    }
+
+    /// Top left corner of a square.
+    fn corner(&self, sq: Square) -> (u32, u32) {
+        let col = self.orientation.fold(sq.file(), 7 - sq.file()) as u32;
+        let row = self.orientation.fold(7 - sq.rank(), sq.rank()) as u32;
+        (self.margin() + col * self.size, self.margin() + row * self.size)
+    }
+
+    fn center(&self, sq: Square) -> (u32, u32) {
+        let (x, y) = self.corner(sq);
+        (x + self.size / 2, y + self.size / 2)
+    }
+
+    fn rect(&self, svg: &mut String, sq: Square, fill: &str, opacity: &str) {
+        let (x, y) = self.corner(sq);
+        svg.push_str(&format!("<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{}\" fill-opacity=\"{}\" />",
+                              x, y, fill, opacity, size = self.size));
+    }
+}
+
+impl Default for SvgOpts {
+    fn default() -> SvgOpts {
+        SvgOpts::new()
+    }
+}
+
+/// Piece drawings by Colin M.L. Burnett (Cburnett), on a 45x45 grid. GPLv2+.
+fn piece_def(color: Color, role: Role) -> &'static str {
+    match (color, role) {
+        (Color::White, Role::Pawn) => r##"<g id="white-pawn"><path d="M22.5 9c-2.21 0-4 1.79-4 4 0 .89.29 1.71.78 2.38C17.33 16.5 16 18.59 16 21c0 2.03.94 3.84 2.41 5.03-3 1.06-7.41 5.55-7.41 13.47h23c0-7.92-4.41-12.41-7.41-13.47 1.47-1.19 2.41-3 2.41-5.03 0-2.41-1.33-4.5-3.28-5.62.49-.67.78-1.49.78-2.38 0-2.21-1.79-4-4-4z" fill="#fff" stroke="#000" stroke-width="1.5" stroke-linecap="round" /></g>"##,
+        (Color::White, Role::Knight) => r##"<g id="white-knight" fill="none" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M22 10c10.5 1 16.5 8 16 29H15c0-9 10-6.5 8-21" fill="#fff" /><path d="M24 18c.38 2.91-5.55 7.37-8 9-3 2-2.82 4.34-5 4-1.042-.94 1.41-3.04 0-3-1 0 .19 1.23-1 2-1 0-4.003 1-4-4 0-2 6-12 6-12s1.89-1.9 2-3.5c-.73-.994-.5-2-.5-3 1-1 3 2.5 3 2.5h2s.78-1.992 2.5-3c1 0 1 3 1 3" fill="#fff" /><path d="M9.5 25.5a.5.5 0 1 1-1 0 .5.5 0 1 1 1 0zm5.433-9.75a.5 1.5 30 1 1-.866-.5.5 1.5 30 1 1 .866.5z" fill="#000" /></g>"##,
+        (Color::White, Role::Bishop) => r##"<g id="white-bishop" fill="none" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><g fill="#fff" stroke-linecap="butt"><path d="M9 36c3.39-.97 10.11.43 13.5-2 3.39 2.43 10.11 1.03 13.5 2 0 0 1.65.54 3 2-.68.97-1.65.99-3 .5-3.39-.97-10.11.46-13.5-1-3.39 1.46-10.11.03-13.5 1-1.354.49-2.323.47-3-.5 1.354-1.94 3-2 3-2zM15 32c2.5 2.5 12.5 2.5 15 0 .5-1.5 0-2 0-2 0-2.5-2.5-4-2.5-4 5.5-1.5 6-11.5-5-15.5-11 4-10.5 14-5 15.5 0 0-2.5 1.5-2.5 4 0 0-.5.5 0 2zM25 8a2.5 2.5 0 1 1-5 0 2.5 2.5 0 1 1 5 0z" /></g><path d="M17.5 26h10M15 30h15m-7.5-14.5v5M20 18h5" stroke-linejoin="miter" /></g>"##,
+        (Color::White, Role::Rook) => r##"<g id="white-rook" fill="#fff" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M9 39h27v-3H9v3zM12 36v-4h21v4H12zM11 14V9h4v2h5V9h5v2h5V9h4v5" stroke-linecap="butt" /><path d="M34 14l-3 3H14l-3-3" /><path d="M31 17v12.5H14V17" stroke-linecap="butt" stroke-linejoin="miter" /><path d="M31 29.5l1.5 2.5h-20l1.5-2.5" /><path d="M11 14h23" fill="none" stroke-linejoin="miter" /></g>"##,
+        (Color::White, Role::Queen) => r##"<g id="white-queen" fill="#fff" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M8 12a2 2 0 1 1-4 0 2 2 0 1 1 4 0zM24.5 7.5a2 2 0 1 1-4 0 2 2 0 1 1 4 0zM41 12a2 2 0 1 1-4 0 2 2 0 1 1 4 0zM16 8.5a2 2 0 1 1-4 0 2 2 0 1 1 4 0zM33 9a2 2 0 1 1-4 0 2 2 0 1 1 4 0z" /><path d="M9 26c8.5-1.5 21-1.5 27 0l2-12-7 11V11l-5.5 13.5-3-15-3 15-5.5-14V25L7 14l2 12zM9 26c0 2 1.5 2 2.5 4 1 1.5 1 1 .5 3.5-1.5 1-1.5 2.5-1.5 2.5-1.5 1.5.5 2.5.5 2.5 6.5 1 16.5 1 23 0 0 0 1.5-1 0-2.5 0 0 .5-1.5-1-2.5-.5-2.5-.5-2 .5-3.5 1-2 2.5-2 2.5-4-8.5-1.5-18.5-1.5-27 0z" stroke-linecap="butt" /><path d="M11.5 30c3.5-1 18.5-1 22 0M12 33.5c6-1 15-1 21 0" fill="none" /></g>"##,
+        (Color::White, Role::King) => r##"<g id="white-king" fill="none" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M22.5 11.63V6M20 8h5" stroke-linejoin="miter" /><path d="M22.5 25s4.5-7.5 3-10.5c0 0-1-2.5-3-2.5s-3 2.5-3 2.5c-1.5 3 3 10.5 3 10.5" fill="#fff" stroke-linecap="butt" stroke-linejoin="miter" /><path d="M11.5 37c5.5 3.5 15.5 3.5 21 0v-7s9-4.5 6-10.5c-4-6.5-13.5-3.5-16 4V27v-3.5c-3.5-7.5-13-10.5-16-4-3 6 5 10 5 10V37z" fill="#fff" /><path d="M11.5 30c5.5-3 15.5-3 21 0m-21 3.5c5.5-3 15.5-3 21 0m-21 3.5c5.5-3 15.5-3 21 0" /></g>"##,
+        (Color::Black, Role::Pawn) => r##"<g id="black-pawn"><path d="M22.5 9c-2.21 0-4 1.79-4 4 0 .89.29 1.71.78 2.38C17.33 16.5 16 18.59 16 21c0 2.03.94 3.84 2.41 5.03-3 1.06-7.41 5.55-7.41 13.47h23c0-7.92-4.41-12.41-7.41-13.47 1.47-1.19 2.41-3 2.41-5.03 0-2.41-1.33-4.5-3.28-5.62.49-.67.78-1.49.78-2.38 0-2.21-1.79-4-4-4z" fill="#000" stroke="#000" stroke-width="1.5" stroke-linecap="round" /></g>"##,
+        (Color::Black, Role::Knight) => r##"<g id="black-knight" fill="none" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M22 10c10.5 1 16.5 8 16 29H15c0-9 10-6.5 8-21" fill="#000" /><path d="M24 18c.38 2.91-5.55 7.37-8 9-3 2-2.82 4.34-5 4-1.042-.94 1.41-3.04 0-3-1 0 .19 1.23-1 2-1 0-4.003 1-4-4 0-2 6-12 6-12s1.89-1.9 2-3.5c-.73-.994-.5-2-.5-3 1-1 3 2.5 3 2.5h2s.78-1.992 2.5-3c1 0 1 3 1 3" fill="#000" /><path d="M9.5 25.5a.5.5 0 1 1-1 0 .5.5 0 1 1 1 0zm5.433-9.75a.5 1.5 30 1 1-.866-.5.5 1.5 30 1 1 .866.5z" fill="#ececec" stroke="#ececec" /><path d="M24.55 10.4l-.45 1.45.5.15c3.15 1 5.65 2.49 7.9 6.75S35.75 29.06 35.25 39l-.05.5h2.25l.05-.5c.5-10.06-.88-16.85-3.25-21.34-2.37-4.49-5.79-6.64-9.19-7.16l-.51-.1z" fill="#ececec" stroke="none" /></g>"##,
+        (Color::Black, Role::Bishop) => r##"<g id="black-bishop" fill="none" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M9 36c3.39-.97 10.11.43 13.5-2 3.39 2.43 10.11 1.03 13.5 2 0 0 1.65.54 3 2-.68.97-1.65.99-3 .5-3.39-.97-10.11.46-13.5-1-3.39 1.46-10.11.03-13.5 1-1.354.49-2.323.47-3-.5 1.354-1.94 3-2 3-2zm6-4c2.5 2.5 12.5 2.5 15 0 .5-1.5 0-2 0-2 0-2.5-2.5-4-2.5-4 5.5-1.5 6-11.5-5-15.5-11 4-10.5 14-5 15.5 0 0-2.5 1.5-2.5 4 0 0-.5.5 0 2zM25 8a2.5 2.5 0 1 1-5 0 2.5 2.5 0 1 1 5 0z" fill="#000" stroke-linecap="butt" /><path d="M17.5 26h10M15 30h15m-7.5-14.5v5M20 18h5" stroke="#fff" stroke-linejoin="miter" /></g>"##,
+        (Color::Black, Role::Rook) => r##"<g id="black-rook" fill="#000" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M9 39h27v-3H9v3zM12.5 32l1.5-2.5h17l1.5 2.5h-20zM12 36v-4h21v4H12z" stroke-linecap="butt" /><path d="M14 29.5v-13h17v13H14z" stroke-linecap="butt" stroke-linejoin="miter" /><path d="M14 16.5L11 14h23l-3 2.5H14zM11 14V9h4v2h5V9h5v2h5V9h4v5H11z" stroke-linecap="butt" /><path d="M12 35.5h21M13 31.5h19M14 29.5h17M14 16.5h17M11 14h23" fill="none" stroke="#fff" stroke-width="1" stroke-linejoin="miter" /></g>"##,
+        (Color::Black, Role::Queen) => r##"<g id="black-queen" fill="#000" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><g stroke="none"><circle cx="6" cy="12" r="2.75" /><circle cx="14" cy="9" r="2.75" /><circle cx="22.5" cy="8" r="2.75" /><circle cx="31" cy="9" r="2.75" /><circle cx="39" cy="12" r="2.75" /></g><path d="M9 26c8.5-1.5 21-1.5 27 0l2.5-12.5L31 25l-.3-14.1-5.2 13.6-3-14.5-3 14.5-5.2-13.6L14 25 6.5 13.5 9 26zM9 26c0 2 1.5 2 2.5 4 1 1.5 1 1 .5 3.5-1.5 1-1.5 2.5-1.5 2.5-1.5 1.5.5 2.5.5 2.5 6.5 1 16.5 1 23 0 0 0 1.5-1 0-2.5 0 0 .5-1.5-1-2.5-.5-2.5-.5-2 .5-3.5 1-2 2.5-2 2.5-4-8.5-1.5-18.5-1.5-27 0z" stroke-linecap="butt" /><path d="M11 38.5a35 35 1 0 0 23 0" fill="none" stroke-linecap="butt" /><path d="M11 29a35 35 1 0 1 23 0M12.5 31.5h20M11.5 34.5a35 35 1 0 0 22 0M10.5 37.5a35 35 1 0 0 24 0" fill="none" stroke="#fff" /></g>"##,
+        (Color::Black, Role::King) => r##"<g id="black-king" fill="none" fill-rule="evenodd" stroke="#000" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round"><path d="M22.5 11.63V6" stroke-linejoin="miter" /><path d="M22.5 25s4.5-7.5 3-10.5c0 0-1-2.5-3-2.5s-3 2.5-3 2.5c-1.5 3 3 10.5 3 10.5" fill="#000" stroke-linecap="butt" stroke-linejoin="miter" /><path d="M11.5 37c5.5 3.5 15.5 3.5 21 0v-7s9-4.5 6-10.5c-4-6.5-13.5-3.5-16 4V27v-3.5c-3.5-7.5-13-10.5-16-4-3 6 5 10 5 10V37z" fill="#000" /><path d="M20 8h5" stroke-linejoin="miter" /><path d="M32 29.5s8.5-4 6.03-9.65C34.15 14 25 18 22.5 24.5l.01 2.1-.01-2.1C20 18 9.906 14 6.997 19.85c-2.497 5.65 4.853 9 4.853 9M11.5 30c5.5-3 15.5-3 21 0m-21 3.5c5.5-3 15.5-3 21 0m-21 3.5c5.5-3 15.5-3 21 0" stroke="#fff" /></g>"##,
+    }
+}
+
+fn role_name(role: Role) -> &'static str {
+    match role {
+        Role::Pawn => "pawn",
+        Role::Knight => "knight",
+        Role::Bishop => "bishop",
+        Role::Rook => "rook",
+        Role::Queen => "queen",
+        Role::King => "king",
+    }
+}
+
+/// Render a board as an SVG image.
+pub fn board(board: &Board, opts: &SvgOpts) -> String {
+    let size = opts.size;
+    let full = 8 * size + 2 * opts.margin();
+    let mut svg = String::new();
+
+    svg.push_str(&format!("<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" version=\"1.1\" width=\"{full}\" height=\"{full}\" viewBox=\"0 0 {full} {full}\">",
+                          full = full));
+
+    // Piece glyphs, arrow heads and the check gradient.
+    svg.push_str("<defs>");
+    for &color in &[Color::White, Color::Black] {
+        for &role in &ROLES {
+            svg.push_str(piece_def(color, role));
+        }
+    }
+    svg.push_str(&format!("<marker id=\"arrowhead\" viewBox=\"0 0 10 10\" refX=\"5\" refY=\"5\" markerWidth=\"3\" markerHeight=\"3\" orient=\"auto\"><path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"{}\" /></marker>", ARROW));
+    svg.push_str("<radialGradient id=\"check\"><stop offset=\"0%\" stop-color=\"#ff0000\" /><stop offset=\"50%\" stop-color=\"#e70000\" /><stop offset=\"100%\" stop-color=\"#9e0000\" stop-opacity=\"0\" /></radialGradient>");
+    svg.push_str("</defs>");
+
+    // Squares and overlays.
+    for sq in Bitboard::ALL {
+        opts.rect(&mut svg, sq, if sq.is_light() { LIGHT } else { DARK }, "1");
+
+        if opts.last_move.contains(sq) {
+            opts.rect(&mut svg, sq, LAST_MOVE, "0.4");
+        }
+
+        if opts.highlights.contains(sq) {
+            opts.rect(&mut svg, sq, HIGHLIGHT, "0.5");
+        }
+
+        if opts.check == Some(sq) {
+            opts.rect(&mut svg, sq, "url(#check)", "1");
+        }
+    }
+
+    // Coordinates.
+    if opts.coordinates {
+        let margin = opts.margin();
+        for i in 0..8 {
+            let file = Square::from_coords(i, 0).expect("valid coords");
+            let (x, _) = opts.center(file);
+            svg.push_str(&format!("<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\">{}</text>",
+                                  x, full - margin / 3, margin * 3 / 4, file.file_char()));
+
+            let rank = Square::from_coords(0, i).expect("valid coords");
+            let (_, y) = opts.center(rank);
+            svg.push_str(&format!("<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\">{}</text>",
+                                  margin / 2, y + margin / 4, margin * 3 / 4, rank.rank_char()));
+        }
+    }
+
+    // Pieces, scaled from the 45x45 grid of the piece set.
+    let scale = if size == 45 { String::new() } else { format!(" scale({})", f64::from(size) / 45.0) };
+    for sq in board.occupied() {
+        if let Some(piece) = board.piece_at(sq) {
+            let (x, y) = opts.corner(sq);
+            svg.push_str(&format!("<use xlink:href=\"#{}-{}\" transform=\"translate({}, {}){}\" />",
+                                  piece.color.fold("white", "black"), role_name(piece.role), x, y, scale));
+        }
+    }
+
+    // Arrows.
+    for &(from, to) in &opts.arrows {
+        let (x1, y1) = opts.center(from);
+        let (x2, y2) = opts.center(to);
+        svg.push_str(&format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" stroke-opacity=\"0.6\" marker-end=\"url(#arrowhead)\" />",
+                              x1, y1, x2, y2, ARROW, size / 5));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Render the board of a position as an SVG image, marking the king of the
+/// side to move if it is in check.
+pub fn position<P: Position>(pos: &P, opts: &SvgOpts) -> String {
+    let mut opts = opts.clone();
+    if pos.checkers().any() {
+        opts.check(pos.board().king_of(pos.turn()));
+    }
+    board(pos.board(), &opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fen::Fen;
+    use position::Chess;
+
+    #[test]
+    fn test_board() {
+        let svg = SvgOpts::new().board(&Board::new());
+        assert_eq!(svg.matches("<use ").count(), 32);
+        assert!(svg.contains("width=\"360\""));
+        assert!(!svg.contains("fill=\"url(#check)\""));
+        assert!(!svg.contains("<text"));
+        assert_eq!(svg.matches("<g id=\"").count(), 12);
+
+        let svg = SvgOpts::new().size(90).board(&Board::new());
+        assert!(svg.contains("<use xlink:href=\"#black-rook\" transform=\"translate(0, 0) scale(2)\" />"));
+    }
+
+    #[test]
+    fn test_orientation() {
+        let opts = SvgOpts::new();
+        assert_eq!(opts.corner(Square::A8), (0, 0));
+        assert_eq!(opts.corner(Square::H1), (315, 315));
+
+        let mut opts = SvgOpts::new();
+        opts.orientation(Color::Black).coordinates(true).size(40);
+        assert_eq!(opts.corner(Square::H1), (20, 20));
+        assert_eq!(opts.center(Square::A8), (320, 320));
+    }
+
+    #[test]
+    fn test_check() {
+        let fen: Fen = "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+        let svg = SvgOpts::new().arrow(Square::D8, Square::H4).position(&pos);
+        assert_eq!(svg.matches("fill=\"url(#check)\"").count(), 1);
+        assert_eq!(svg.matches("<line ").count(), 1);
+    }
+}
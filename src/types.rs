@@ -145,6 +145,25 @@ impl Piece {
             role.of(Color::from_bool(32 & ch as u8 == 0))
         })
     }
+
+    /// Gets the Unicode chess symbol of the piece, e.g. `♘` for a white
+    /// knight.
+    pub fn figurine(&self) -> char {
+        match (self.color, self.role) {
+            (Color::White, Role::Pawn) => '♙',
+            (Color::White, Role::Knight) => '♘',
+            (Color::White, Role::Bishop) => '♗',
+            (Color::White, Role::Rook) => '♖',
+            (Color::White, Role::Queen) => '♕',
+            (Color::White, Role::King) => '♔',
+            (Color::Black, Role::Pawn) => '♟',
+            (Color::Black, Role::Knight) => '♞',
+            (Color::Black, Role::Bishop) => '♝',
+            (Color::Black, Role::Rook) => '♜',
+            (Color::Black, Role::Queen) => '♛',
+            (Color::Black, Role::King) => '♚',
+        }
+    }
 }
 
 /// Information about a move.
@@ -95,8 +95,10 @@ use std::str::FromStr;
 use std::error::Error;
 
 use square::Square;
-use types::{Role, Move};
-use position::{Position, IllegalMove};
+use types::{Color, Role, Move};
+use bitboard::Bitboard;
+use setup::{CastlingMode, CastlingSide};
+use position::{Position, IllegalMove, IllegalMoveReason};
 
 /// Error when parsing an invalid UCI.
 pub struct InvalidUci {
@@ -163,17 +165,13 @@ impl fmt::Display for Uci {
 }
 
 impl<'a> From<&'a Move> for Uci {
+    /// Converts a move to UCI notation, writing castling moves in
+    /// Chess960 notation (king to rook). See [`Uci::from_move()`] for
+    /// standard notation.
+    ///
+    /// [`Uci::from_move()`]: enum.Uci.html#method.from_move
     fn from(m: &'a Move) -> Uci {
-        match *m {
-            Move::Normal { from, to, promotion, .. } =>
-                Uci::Normal { from, to, promotion },
-            Move::EnPassant { from, to, .. } =>
-                Uci::Normal { from, to, promotion: None },
-            Move::Castle { king, rook } =>
-                Uci::Normal { from: king, to: rook, promotion: None },  // Chess960-style
-            Move::Put { role, to } =>
-                Uci::Put { role, to },
-        }
+        Uci::from_move(m, CastlingMode::Chess960)
     }
 }
 
@@ -218,47 +216,224 @@ impl Uci {
         }
     }
 
+    /// Converts a move to UCI notation, writing castling moves according
+    /// to `mode`.
+    ///
+    /// Standard notation can only express castling with the king and rook on
+    /// their usual squares. Other castling moves are written as the king
+    /// capturing its own rook, which
+    /// [`to_move_with_mode()`](#method.to_move_with_mode) also accepts in
+    /// standard mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use shakmaty::{Square, Move, CastlingMode};
+    /// use shakmaty::uci::Uci;
+    ///
+    /// let m = Move::Castle { king: Square::E1, rook: Square::H1 };
+    ///
+    /// assert_eq!(Uci::from_move(&m, CastlingMode::Standard).to_string(), "e1g1");
+    /// assert_eq!(Uci::from_move(&m, CastlingMode::Chess960).to_string(), "e1h1");
+    /// ```
+    pub fn from_move(m: &Move, mode: CastlingMode) -> Uci {
+        match *m {
+            Move::Normal { from, to, promotion, .. } =>
+                Uci::Normal { from, to, promotion },
+            Move::EnPassant { from, to, .. } =>
+                Uci::Normal { from, to, promotion: None },
+            Move::Castle { king, rook } => match mode {
+                CastlingMode::Chess960 =>
+                    Uci::Normal { from: king, to: rook, promotion: None },
+                CastlingMode::Standard => Uci::Normal {
+                    from: king,
+                    to: standard_castling_target(king, rook).unwrap_or(rook),
+                    promotion: None,
+                },
+            },
+            Move::Put { role, to } =>
+                Uci::Put { role, to },
+        }
+    }
+
     /// Tries to convert the `Uci` to a legal [`Move`] in the context of a
     /// position.
     ///
+    /// Castling moves are accepted in both standard and Chess960 notation.
+    /// Use [`to_move_with_mode()`] to accept only one of them.
+    ///
     /// # Errors
     ///
-    /// Returns [`IllegalMove`] if the move is not legal.
+    /// Returns [`IllegalMove`] if the move is not legal. The move is tried in
+    /// Chess960 notation first, so the error is the one from interpreting it
+    /// in standard notation.
     ///
     /// [`Move`]: ../enum.Move.html
     /// [`IllegalMove`]: ../struct.IllegalMove.html
+    /// [`to_move_with_mode()`]: #method.to_move_with_mode
     pub fn to_move<P: Position>(&self, pos: &P) -> Result<Move, IllegalMove> {
+        self.to_move_with_mode(pos, CastlingMode::Chess960)
+            .or_else(|_| self.to_move_with_mode(pos, CastlingMode::Standard))
+    }
+
+    /// Tries to convert the `Uci` to a legal [`Move`] in the context of a
+    /// position, expecting castling moves in the notation given by `mode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`IllegalMove`] if the move is not legal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// #
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use shakmaty::{Square, Move, CastlingMode};
+    /// use shakmaty::fen::Fen;
+    /// use shakmaty::uci::Uci;
+    /// # use shakmaty::Chess;
+    ///
+    /// let setup: Fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1".parse()?;
+    /// let pos: Chess = setup.position()?;
+    /// let castle = Move::Castle { king: Square::E1, rook: Square::H1 };
+    ///
+    /// let standard: Uci = "e1g1".parse()?;
+    /// assert_eq!(standard.to_move_with_mode(&pos, CastlingMode::Standard)?, castle);
+    /// assert!(standard.to_move_with_mode(&pos, CastlingMode::Chess960).is_err());
+    ///
+    /// let chess960: Uci = "e1h1".parse()?;
+    /// assert_eq!(chess960.to_move_with_mode(&pos, CastlingMode::Chess960)?, castle);
+    /// assert!(chess960.to_move_with_mode(&pos, CastlingMode::Standard).is_err());
+    /// #
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`Move`]: ../enum.Move.html
+    /// [`IllegalMove`]: ../struct.IllegalMove.html
+    pub fn to_move_with_mode<P: Position>(&self, pos: &P, mode: CastlingMode) -> Result<Move, IllegalMove> {
         let candidate = match *self {
             Uci::Normal { from, to, promotion } => {
-                let role = pos.board().role_at(from).ok_or(IllegalMove {})?;
+                let role = pos.board().role_at(from)
+                    .ok_or(IllegalMove { reason: IllegalMoveReason::NoPiece })?;
 
                 if promotion.is_some() && role != Role::Pawn {
-                    return Err(IllegalMove {})
+                    return Err(IllegalMove { reason: IllegalMoveReason::InvalidPromotion })
                 }
 
-                if role == Role::King && pos.castling_rights().contains(to) {
+                if role == Role::King && mode == CastlingMode::Chess960 &&
+                   pos.castling_rights().contains(to) {
+                    Move::Castle { king: from, rook: to }
+                } else if role == Role::King && mode == CastlingMode::Standard &&
+                          (pos.castling_rights() & pos.us()).contains(to) &&
+                          standard_castling_target(from, to).is_none() {
+                    // Castling that can not be written in standard notation.
                     Move::Castle { king: from, rook: to }
-                } else if role == Role::King &&
+                } else if role == Role::King && mode == CastlingMode::Standard &&
                           from == pos.turn().fold(Square::E1, Square::E8) &&
-                          to.rank() == pos.turn().fold(0, 7) &&
+                          to.rank() == from.rank() &&
                           from.distance(to) == 2 {
-                    if from.file() < to.file() {
-                        Move::Castle { king: from, rook: pos.turn().fold(Square::H1, Square::H8) }
+                    let candidates = pos.castling_rights() & pos.us() & Bitboard::rank(from.rank());
+                    let rook = if from < to {
+                        candidates.last().filter(|&rook| from < rook)
                     } else {
-                        Move::Castle { king: from, rook: pos.turn().fold(Square::A1, Square::A8) }
+                        candidates.first().filter(|&rook| rook < from)
+                    };
+                    Move::Castle {
+                        king: from,
+                        rook: rook.ok_or(IllegalMove { reason: IllegalMoveReason::NoCastlingRights })?,
                     }
                 } else {
                     Move::Normal { role, from, capture: pos.board().role_at(to), to, promotion }
                 }
             },
             Uci::Put { role, to } => Move::Put { role, to },
-            Uci::Null => return Err(IllegalMove {})
+            Uci::Null => return Err(IllegalMove { reason: IllegalMoveReason::Other })
         };
 
         if pos.is_legal(&candidate) {
             Ok(candidate)
         } else {
-            Err(IllegalMove {})
+            Err(IllegalMove::explain(pos, &candidate))
+        }
+    }
+}
+
+/// The target square of the king in standard castling notation, if the king
+/// and rook are on their standard squares.
+fn standard_castling_target(king: Square, rook: Square) -> Option<Square> {
+    if king.file() != 4 || (king.rank() != 0 && king.rank() != 7) ||
+       rook.rank() != king.rank() || (rook.file() != 0 && rook.file() != 7) {
+        return None;
+    }
+
+    let side = if rook < king { CastlingSide::QueenSide } else { CastlingSide::KingSide };
+    Some(side.king_to(Color::White).combine(king))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fen::Fen;
+    use position::Chess;
+    use movelist::MoveList;
+
+    #[test]
+    fn test_chess960_castling() {
+        // King on f1 can castle to g1 (queenside rook on b1).
+        let fen: Fen = "4k3/8/8/8/8/8/8/1R3KR1 w GB - 0 1".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        let castle = Move::Castle { king: Square::F1, rook: Square::G1 };
+        let uci: Uci = "f1g1".parse().expect("valid uci");
+        assert_eq!(uci.to_move_with_mode(&pos, CastlingMode::Chess960).expect("legal"), castle);
+
+        // Not expressible in standard notation, so written as king to rook.
+        assert_eq!(uci.to_move_with_mode(&pos, CastlingMode::Standard).expect("legal"), castle);
+        assert!("f1h1".parse::<Uci>().expect("valid uci").to_move_with_mode(&pos, CastlingMode::Standard).is_err());
+
+        let long = Move::Castle { king: Square::F1, rook: Square::B1 };
+        assert!(pos.is_legal(&long));
+    }
+
+    #[test]
+    fn test_castling_roundtrip() {
+        for fen in &["4k3/8/8/8/8/8/8/1R3KR1 w GB - 0 1",
+                     "4k3/8/8/8/8/8/8/RR4KR w HB - 0 1",
+                     "4k3/8/8/8/8/8/8/R3K1R1 w GA - 0 1",
+                     "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1"] {
+            let fen: Fen = fen.parse().expect("valid fen");
+            let pos: Chess = fen.position().expect("legal position");
+
+            let mut moves = MoveList::new();
+            pos.castling_moves(CastlingSide::KingSide, &mut moves);
+            pos.castling_moves(CastlingSide::QueenSide, &mut moves);
+            assert_eq!(moves.len(), 2);
+
+            for m in &moves {
+                for &mode in &[CastlingMode::Standard, CastlingMode::Chess960] {
+                    let uci = Uci::from_move(m, mode);
+                    assert_eq!(uci.to_move_with_mode(&pos, mode).as_ref(), Ok(m), "{} in {:?} mode", uci, mode);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_standard_castling() {
+        let fen: Fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1".parse().expect("valid fen");
+        let pos: Chess = fen.position().expect("legal position");
+
+        for &(uci, mode) in &[("e8c8", CastlingMode::Standard), ("e8a8", CastlingMode::Chess960)] {
+            let m = uci.parse::<Uci>().expect("valid uci").to_move_with_mode(&pos, mode).expect("legal");
+            assert_eq!(m, Move::Castle { king: Square::E8, rook: Square::A8 });
+            assert_eq!(Uci::from_move(&m, mode).to_string(), uci);
+            assert_eq!(uci.parse::<Uci>().expect("valid uci").to_move(&pos).expect("legal"), m);
         }
     }
 }
@@ -0,0 +1,109 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::cmp::min;
+
+use setup::Setup;
+use types::{Color, White, Black, ROLES};
+
+include!(concat!(env!("OUT_DIR"), "/zobrist.rs")); // generated by build.rs
+
+#[inline]
+fn piece_key(color: Color, role_index: usize, sq: usize) -> u64 {
+    // This is safe because colors, roles and squares are in bounds.
+    unsafe { *PIECE_KEYS.get_unchecked((color as usize * 6 + role_index) * 64 + sq) }
+}
+
+/// Computes a Zobrist hash of the board, turn, castling rights, en passant
+/// square, pockets and remaining checks of a setup.
+///
+/// Pocket counts and remaining checks are hashed saturating at 15 and 3
+/// respectively.
+pub fn hash(setup: &Setup) -> u64 {
+    let mut zobrist = 0;
+
+    for (sq, piece) in setup.board().pieces() {
+        zobrist ^= piece_key(piece.color, piece.role as usize, usize::from(sq));
+    }
+
+    if setup.turn().is_white() {
+        zobrist ^= TURN_KEYS[0];
+    }
+
+    for sq in setup.castling_rights() {
+        zobrist ^= CASTLING_KEYS[usize::from(sq)];
+    }
+
+    if let Some(sq) = setup.ep_square() {
+        zobrist ^= EP_KEYS[sq.file() as usize];
+    }
+
+    if let Some(pockets) = setup.pockets() {
+        for &color in &[White, Black] {
+            for &role in &ROLES {
+                let count = min(pockets.by_color(color).by_role(role), 15) as usize;
+                zobrist ^= POCKET_KEYS[(color as usize * 6 + role as usize) * 16 + count];
+            }
+        }
+    }
+
+    if let Some(remaining_checks) = setup.remaining_checks() {
+        for &color in &[White, Black] {
+            let count = min(remaining_checks.by_color(color), 3) as usize;
+            zobrist ^= CHECK_KEYS[color as usize * 4 + count];
+        }
+    }
+
+    zobrist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fen::Fen;
+    use position::{Chess, Position};
+    use square::Square;
+    use types::{Move, Role};
+
+    #[test]
+    fn test_transposition() {
+        let a = Move::Normal { role: Role::Knight, from: Square::G1, capture: None, to: Square::F3, promotion: None };
+        let b = Move::Normal { role: Role::Knight, from: Square::G8, capture: None, to: Square::F6, promotion: None };
+        let c = Move::Normal { role: Role::Knight, from: Square::B1, capture: None, to: Square::C3, promotion: None };
+
+        let mut first = Chess::default();
+        first.play_unchecked(&a);
+        first.play_unchecked(&b);
+        first.play_unchecked(&c);
+
+        let mut second = Chess::default();
+        second.play_unchecked(&c);
+        second.play_unchecked(&b);
+        second.play_unchecked(&a);
+
+        assert_eq!(hash(&first), hash(&second));
+        assert_ne!(hash(&first), hash(&Chess::default()));
+    }
+
+    #[test]
+    fn test_turn_and_castling() {
+        let white: Fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq -".parse().expect("valid fen");
+        let black: Fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq -".parse().expect("valid fen");
+        let no_castling: Fen = "r3k2r/8/8/8/8/8/8/R3K2R w - -".parse().expect("valid fen");
+        assert_ne!(hash(&white), hash(&black));
+        assert_ne!(hash(&white), hash(&no_castling));
+    }
+}
@@ -0,0 +1,122 @@
+// This file is part of the shakmaty library.
+// Copyright (C) 2017 Niklas Fiekas <niklas.fiekas@backscattering.de>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "cli")]
+
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn shakmaty(args: &[&str]) -> (bool, String) {
+    // Integration tests live in target/<profile>/deps, next to the
+    // directory with the binary.
+    let mut path = env::current_exe().expect("test executable path");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    let path: PathBuf = path.join(format!("shakmaty{}", env::consts::EXE_SUFFIX));
+
+    let output = Command::new(path).args(args).output().expect("failed to run shakmaty");
+    (output.status.success(), String::from_utf8(output.stdout).expect("utf-8 output"))
+}
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+#[test]
+fn test_perft() {
+    assert_eq!(shakmaty(&["perft", "startpos", "3"]), (true, "8902\n".to_owned()));
+    assert_eq!(shakmaty(&["perft", KIWIPETE, "2", "--threads", "2", "--hash", "4096"]),
+               (true, "2039\n".to_owned()));
+
+    let (ok, output) = shakmaty(&["perft", "startpos", "2", "--divide"]);
+    assert!(ok);
+    assert!(output.lines().any(|line| line == "e2e4 20"));
+    assert!(output.ends_with("\n\n400\n"));
+
+    let (ok, output) = shakmaty(&["perft", KIWIPETE, "1", "--divide", "--chess960"]);
+    assert!(ok);
+    assert!(output.lines().any(|line| line == "e1h1 1"));
+    assert!(!output.lines().any(|line| line == "e1g1 1"));
+
+    assert!(!shakmaty(&["perft", "startpos"]).0);
+    assert!(!shakmaty(&["perft", "startpos", "2", "--threads"]).0);
+}
+
+#[test]
+fn test_suite() {
+    assert_eq!(shakmaty(&["suite", "tests/tricky.perft", "--node-limit", "10000"]),
+               (true, "ok\n".to_owned()));
+    assert!(!shakmaty(&["suite"]).0);
+    assert!(!shakmaty(&["suite", "tests/does-not-exist.perft"]).0);
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(shakmaty(&["validate", "startpos"]), (true, "ok\n".to_owned()));
+    assert_eq!(shakmaty(&["validate", "8/8/8/8/8/8/8/8 w - - 0 1"]),
+               (false, "EMPTY_BOARD\nMISSING_KING\n".to_owned()));
+
+    let too_many_pawns = "4k3/8/8/8/8/P7/PPPPPPPP/4K3 w - - 0 1";
+    assert_eq!(shakmaty(&["validate", too_many_pawns]), (true, "ok\n".to_owned()));
+    assert_eq!(shakmaty(&["validate", too_many_pawns, "--strict"]),
+               (false, "TOO_MANY_PAWNS\n".to_owned()));
+
+    assert!(!shakmaty(&["validate"]).0);
+}
+
+#[test]
+fn test_legals() {
+    let (ok, output) = shakmaty(&["legals", "startpos"]);
+    assert!(ok);
+    assert_eq!(output.lines().count(), 20);
+    assert!(output.lines().any(|line| line == "g1f3 Nf3"));
+
+    let (ok, output) = shakmaty(&["legals", KIWIPETE, "--chess960"]);
+    assert!(ok);
+    assert!(output.lines().any(|line| line == "e1a1 O-O-O"));
+
+    assert!(!shakmaty(&["legals"]).0);
+}
+
+#[test]
+fn test_san2uci() {
+    assert_eq!(shakmaty(&["san2uci", "startpos", "e4", "e5", "Nf3"]),
+               (true, "e2e4\ne7e5\ng1f3\n".to_owned()));
+    assert_eq!(shakmaty(&["san2uci", KIWIPETE, "O-O"]), (true, "e1g1\n".to_owned()));
+    assert_eq!(shakmaty(&["san2uci", KIWIPETE, "O-O", "--chess960"]), (true, "e1h1\n".to_owned()));
+    assert!(!shakmaty(&["san2uci", "startpos", "e5"]).0);
+    assert!(!shakmaty(&["san2uci"]).0);
+}
+
+#[test]
+fn test_uci2san() {
+    assert_eq!(shakmaty(&["uci2san", "startpos", "e2e4", "e7e5", "g1f3"]),
+               (true, "e4\ne5\nNf3\n".to_owned()));
+    assert_eq!(shakmaty(&["uci2san", KIWIPETE, "e1g1"]), (true, "O-O\n".to_owned()));
+    assert_eq!(shakmaty(&["uci2san", KIWIPETE, "e1h1", "--chess960"]), (true, "O-O\n".to_owned()));
+    assert!(!shakmaty(&["uci2san", "startpos", "e2e5"]).0);
+    assert!(!shakmaty(&["uci2san"]).0);
+}
+
+#[test]
+fn test_help() {
+    let (ok, output) = shakmaty(&["help"]);
+    assert!(ok);
+    assert!(output.starts_with("Usage: shakmaty"));
+    assert!(!shakmaty(&[]).0);
+    assert!(!shakmaty(&["unknown"]).0);
+}
@@ -16,49 +16,27 @@
 
 extern crate shakmaty;
 
-use shakmaty::Position;
-use shakmaty::Chess;
-use shakmaty::fen::Fen;
-use shakmaty::perft;
+use shakmaty::{Position, Chess, Perft};
+use shakmaty::perft::Suite;
 
-use std::io::BufReader;
-use std::io::prelude::*;
 use std::fs::File;
+use std::io::prelude::*;
 
-fn test_perft_file<P: Position + Default + Clone>(path: &str, node_limit: usize) {
-    let file = File::open(path).expect("failed to open test suite");
-    let reader = BufReader::new(file);
-
-    let mut pos = P::default();
-
-    for line in reader.lines().map(|l| l.unwrap()) {
-        println!("{}", line);
-
-        let trimmed = line.trim();
-        let mut slices = trimmed.splitn(2, ' ');
-
-        match slices.next() {
-            Some("epd") => {
-                pos = slices.next().expect("missing epd")
-                            .parse::<Fen>().expect("invalid fen")
-                            .position().expect("illegal fen");
-            },
-            Some("perft") => {
-                let mut params = slices.next().expect("missing perft params").splitn(2, ' ');
-
-                let depth = params.next().expect("missing perft depth")
-                                  .parse().expect("depth not an integer");
+fn test_perft_file<P>(path: &str, node_limit: u64)
+    where P: Position + Clone + Send + Sync + 'static
+{
+    let mut file = File::open(path).expect("failed to open test suite");
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer).expect("failed to read test suite");
 
-                let nodes = params.next().expect("missing perft nodes")
-                                  .parse().expect("nodes not an integer");
+    let suite = Suite::from_bytes(&buffer).expect("valid test suite");
+    let failures = suite.run::<P>(&Perft::new(), node_limit);
 
-                if nodes <= node_limit {
-                    assert_eq!(perft(&pos, depth), nodes);
-                }
-            },
-            _ => {},
-        }
+    for failure in &failures {
+        println!("{}", failure);
     }
+
+    assert!(failures.is_empty());
 }
 
 #[test]